@@ -0,0 +1,2 @@
+/* Declared by the C standard library. */
+int abs(int x);
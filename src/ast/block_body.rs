@@ -2,7 +2,7 @@ use super::{Comment, Expr, Statement};
 use crate::location::{HasSpan, Span};
 use std::fmt::Debug;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum BlockBody {
     Comment(Comment),
     Stmt(Statement),
@@ -0,0 +1,34 @@
+use super::literal;
+use crate::location::{HasSpan, Span};
+use std::fmt::{Display, Formatter};
+
+/// A C header import node, e.g. `@cimport("stdio.h")`.
+#[derive(Clone, Debug)]
+pub struct CImport {
+    span: Span,
+    pub header: literal::String,
+}
+
+impl CImport {
+    pub fn new(span: Span, header: literal::String) -> Self {
+        Self { span, header }
+    }
+}
+
+impl PartialEq for CImport {
+    fn eq(&self, other: &Self) -> bool {
+        self.header == other.header
+    }
+}
+
+impl Display for CImport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@cimport({})", self.header)
+    }
+}
+
+impl HasSpan for CImport {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
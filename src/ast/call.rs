@@ -6,13 +6,22 @@ use crate::location::{HasSpan, Span};
 #[derive(Debug, PartialEq, Clone)]
 pub struct Call {
     span: Span,
+
+    /// The receiver of an instance call, e.g. `a` in `a.eq?(b)`.
+    pub receiver: Option<Box<Expr>>,
+
     pub callee: Qualifier,
     pub args: Vec<Expr>,
 }
 
 impl Call {
-    pub fn new(span: Span, callee: Qualifier, args: Vec<Expr>) -> Self {
-        Self { span, callee, args }
+    pub fn new(span: Span, receiver: Option<Expr>, callee: Qualifier, args: Vec<Expr>) -> Self {
+        Self {
+            span,
+            receiver: receiver.map(Box::new),
+            callee,
+            args,
+        }
     }
 }
 
@@ -24,6 +33,10 @@ impl HasSpan for Call {
 
 impl Display for Call {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(receiver) = &self.receiver {
+            write!(f, "{}.", receiver)?;
+        }
+
         write!(f, "{}(", self.callee)?;
 
         for (i, e) in self.args.iter().enumerate() {
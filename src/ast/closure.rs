@@ -0,0 +1,104 @@
+use super::{function::Param, BlockBody, Id, TypeExpr};
+use crate::location::{HasSpan, Span};
+use std::fmt::Display;
+
+/// A closure capture list entry, e.g. `x` or `&x`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Capture {
+    pub span: Span,
+    pub id: Id,
+
+    /// `&x` captures by reference, `x` captures a copy.
+    pub by_ref: bool,
+}
+
+impl Capture {
+    pub fn new(span: Span, id: Id, by_ref: bool) -> Self {
+        Self { span, id, by_ref }
+    }
+}
+
+impl Display for Capture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.by_ref {
+            write!(f, "&")?;
+        }
+
+        write!(f, "{}", self.id)
+    }
+}
+
+/// An anonymous function expression, e.g. `fn [x, &y](a: Bool) -> Bool { }`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Closure {
+    span: Span,
+    pub captures: Vec<Capture>,
+    pub params: Vec<Param>,
+
+    /// `None` means no returned value, i.e. `void`.
+    pub return_type: Option<TypeExpr>,
+
+    pub body: Vec<BlockBody>,
+}
+
+impl Closure {
+    pub fn new(
+        span: Span,
+        captures: Vec<Capture>,
+        params: Vec<Param>,
+        return_type: Option<TypeExpr>,
+        body: Vec<BlockBody>,
+    ) -> Self {
+        Self {
+            span,
+            captures,
+            params,
+            return_type,
+            body,
+        }
+    }
+}
+
+impl HasSpan for Closure {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl Display for Closure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fn ")?;
+
+        if !self.captures.is_empty() {
+            write!(f, "[")?;
+
+            for (i, capture) in self.captures.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+
+                write!(f, "{}", capture)?;
+            }
+
+            write!(f, "]")?;
+        }
+
+        write!(f, "(")?;
+
+        for (i, param) in self.params.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "{}", param)?;
+        }
+
+        write!(f, ")")?;
+
+        if let Some(return_type) = &self.return_type {
+            write!(f, " -> {}", return_type)?;
+        }
+
+        write!(f, " {{}}")
+    }
+}
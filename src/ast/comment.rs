@@ -1,7 +1,7 @@
 use crate::location::{HasSpan, Span};
 use std::fmt::{Debug, Display, Formatter};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Comment {
     span: Span,
     pub text: String,
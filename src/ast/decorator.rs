@@ -1,29 +1,48 @@
-use super::Id;
+use super::{literal, Id};
 use crate::location::Span;
 use std::fmt::Display;
 
-/// A decorator node, e.g. `@[Builtin]`.
+/// A decorator node, e.g. `@[Builtin]` or `@[Export("foo")]`.
 #[derive(Clone, Debug)]
 pub struct Decorator {
     span: Span,
     pub id: Id,
+
+    /// Decorator arguments, currently only string literals.
+    pub args: Vec<literal::String>,
 }
 
 impl Decorator {
-    pub fn new(span: Span, id: Id) -> Self {
-        Self { span, id }
+    pub fn new(span: Span, id: Id, args: Vec<literal::String>) -> Self {
+        Self { span, id, args }
     }
 }
 
 impl PartialEq for Decorator {
     fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
+        self.id == other.id && self.args == other.args
     }
 }
 
 impl Display for Decorator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "@[{}]", self.id)
+        write!(f, "@[{}", self.id)?;
+
+        if !self.args.is_empty() {
+            write!(f, "(")?;
+
+            for (i, arg) in self.args.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+
+                write!(f, "{}", arg)?;
+            }
+
+            write!(f, ")")?;
+        }
+
+        write!(f, "]")
     }
 }
 
@@ -0,0 +1,41 @@
+use crate::{
+    ast::Id,
+    location::{HasSpan, Span},
+};
+use std::fmt::Display;
+
+/// An error declaration node, e.g. `pub error NotFound`.
+#[derive(Debug, Clone)]
+pub struct Decl {
+    span: Span,
+    pub r#pub: bool,
+    pub id: Id,
+}
+
+impl Decl {
+    pub fn new(span: Span, r#pub: bool, id: Id) -> Self {
+        Self { span, r#pub, id }
+    }
+}
+
+impl PartialEq for Decl {
+    fn eq(&self, other: &Self) -> bool {
+        self.r#pub == other.r#pub && self.id == other.id
+    }
+}
+
+impl Display for Decl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.r#pub {
+            write!(f, "pub ")?;
+        }
+
+        write!(f, "error {}", self.id)
+    }
+}
+
+impl HasSpan for Decl {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
@@ -0,0 +1,2 @@
+mod decl;
+pub use decl::Decl;
@@ -1,24 +1,36 @@
-use super::{literal, Binop, Call, MacroCall, Qualifier};
+use super::{literal, Binop, Call, Catch, Closure, Interpolation, MacroCall, Qualifier, Try};
 use crate::location::{HasSpan, Span};
 use std::fmt::{Debug, Display};
 
 #[derive(PartialEq, Debug, Clone)]
 pub enum Expr {
     BoolLiteral(literal::Bool),
+    StringLiteral(literal::String),
+    NilLiteral(literal::Nil),
+    Interpolation(Interpolation),
     Ref(Qualifier),
     MacroCall(MacroCall),
     Binop(Binop),
     FunctionCall(Call),
+    Closure(Closure),
+    Try(Try),
+    Catch(Catch),
 }
 
 impl HasSpan for Expr {
     fn span(&self) -> Span {
         match self {
             Expr::BoolLiteral(b) => b.span(),
+            Expr::StringLiteral(s) => s.span(),
+            Expr::NilLiteral(n) => n.span(),
+            Expr::Interpolation(i) => i.span(),
             Expr::Ref(id) => id.span(),
             Expr::MacroCall(m) => m.span(),
             Expr::Binop(b) => b.span(),
             Expr::FunctionCall(c) => c.span(),
+            Expr::Closure(c) => c.span(),
+            Expr::Try(t) => t.span(),
+            Expr::Catch(c) => c.span(),
         }
     }
 }
@@ -27,10 +39,16 @@ impl Display for Expr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Expr::BoolLiteral(b) => write!(f, "{}", b),
+            Expr::StringLiteral(s) => write!(f, "{}", s),
+            Expr::NilLiteral(n) => write!(f, "{}", n),
+            Expr::Interpolation(i) => write!(f, "{}", i),
             Expr::Ref(id) => write!(f, "{}", id),
             Expr::MacroCall(m) => write!(f, "{}", m),
             Expr::Binop(b) => write!(f, "{}", b),
             Expr::FunctionCall(c) => write!(f, "{}", c),
+            Expr::Closure(c) => write!(f, "{}", c),
+            Expr::Try(t) => write!(f, "{}", t),
+            Expr::Catch(c) => write!(f, "{}", c),
         }
     }
 }
@@ -1,35 +1,83 @@
 use std::fmt::Display;
 
 use crate::{
-    ast::Qualifier,
+    ast::{BlockBody, Qualifier, TypeExpr},
     location::{HasSpan, Span},
 };
 
 use super::Param;
 
+/// A function declaration shorthand, written before the function id.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Shorthand {
+    /// `.foo`, an instance function of the enclosing type
+    /// with an implicit `self: Self` parameter.
+    Instance,
+
+    /// `::foo`, a function attached to the enclosing type,
+    /// or to the receiver (i.e. `self` parameter) type if freestanding.
+    Static,
+}
+
+impl Display for Shorthand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Shorthand::Instance => write!(f, "."),
+            Shorthand::Static => write!(f, "::"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Decl {
     span: Span,
     pub r#pub: bool,
+
+    /// An `extern` function is implemented in C.
+    pub r#extern: bool,
+
+    pub shorthand: Option<Shorthand>,
     pub id: Qualifier,
     pub params: Vec<Param>,
-    pub return_type: Qualifier,
+
+    /// Whether the parameters end with `...`, i.e. C variadic arguments.
+    pub variadic: bool,
+
+    /// `None` means no returned value, i.e. `void`.
+    pub return_type: Option<TypeExpr>,
+
+    /// Errors the function may throw, i.e. `throws Foo, Bar`.
+    pub throws: Vec<Qualifier>,
+
+    /// A function declaration with a body is a definition.
+    pub body: Option<Vec<BlockBody>>,
 }
 
 impl Decl {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         span: Span,
         r#pub: bool,
+        r#extern: bool,
+        shorthand: Option<Shorthand>,
         id: Qualifier,
         params: Vec<Param>,
-        return_type: Qualifier,
+        variadic: bool,
+        return_type: Option<TypeExpr>,
+        throws: Vec<Qualifier>,
+        body: Option<Vec<BlockBody>>,
     ) -> Self {
         Self {
             span,
             r#pub,
+            r#extern,
+            shorthand,
             id,
             params,
+            variadic,
             return_type,
+            throws,
+            body,
         }
     }
 }
@@ -46,7 +94,17 @@ impl Display for Decl {
             write!(f, "pub ")?;
         }
 
-        write!(f, "fn {}(", self.id)?;
+        if self.r#extern {
+            write!(f, "extern ")?;
+        }
+
+        write!(f, "fn ")?;
+
+        if let Some(shorthand) = self.shorthand {
+            write!(f, "{}", shorthand)?;
+        }
+
+        write!(f, "{}(", self.id)?;
 
         for (i, param) in self.params.iter().enumerate() {
             if i > 0 {
@@ -56,6 +114,28 @@ impl Display for Decl {
             write!(f, "{}", param)?;
         }
 
-        write!(f, ") -> {}", self.return_type)
+        if self.variadic {
+            if !self.params.is_empty() {
+                write!(f, ", ")?;
+            }
+
+            write!(f, "...")?;
+        }
+
+        write!(f, ")")?;
+
+        if let Some(return_type) = &self.return_type {
+            write!(f, " -> {}", return_type)?;
+        }
+
+        for (i, error) in self.throws.iter().enumerate() {
+            write!(f, "{}{}", if i > 0 { ", " } else { " throws " }, error)?;
+        }
+
+        if self.body.is_some() {
+            write!(f, " {{}}")?;
+        }
+
+        Ok(())
     }
 }
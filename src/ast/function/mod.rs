@@ -1,5 +1,5 @@
 mod decl;
-pub use decl::Decl;
+pub use decl::{Decl, Shorthand};
 
 mod param;
 pub use param::Param;
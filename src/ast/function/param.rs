@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use crate::{
-    ast::{Id, Qualifier},
+    ast::{Id, TypeExpr},
     location::Span,
 };
 
@@ -9,11 +9,11 @@ use crate::{
 pub struct Param {
     pub span: Span,
     pub id: Id,
-    pub r#type: Qualifier,
+    pub r#type: TypeExpr,
 }
 
 impl Param {
-    pub fn new(span: Span, id: Id, r#type: Qualifier) -> Self {
+    pub fn new(span: Span, id: Id, r#type: TypeExpr) -> Self {
         Self { span, id, r#type }
     }
 }
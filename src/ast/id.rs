@@ -2,19 +2,34 @@ use crate::location::{HasSpan, Span};
 use std::fmt::{Debug, Display, Formatter};
 
 /// An Onyx identifier node.
-// TODO: Wrapped ids.
+/// A wrapped id is written in backticks, e.g. `` `hello world` ``,
+/// which allows keywords and otherwise illegal names.
 #[derive(Clone, Debug)]
 pub struct Id {
     span: Span,
     pub value: String,
+    pub wrapped: bool,
 }
 
 impl Id {
     pub fn new(span: Span, value: String) -> Self {
-        Self { span, value }
+        Self {
+            span,
+            value,
+            wrapped: false,
+        }
+    }
+
+    pub fn new_wrapped(span: Span, value: String) -> Self {
+        Self {
+            span,
+            value,
+            wrapped: true,
+        }
     }
 }
 
+/// A raw and a wrapped ids with the same value are equal.
 impl PartialEq for Id {
     fn eq(&self, other: &Self) -> bool {
         self.value == other.value
@@ -0,0 +1,74 @@
+use super::{BlockBody, Expr, Id};
+use crate::location::{HasSpan, Span};
+use std::fmt::{Debug, Display, Formatter};
+
+/// An `if` statement node, e.g. `if x != nil { } else { }`.
+#[derive(Clone, Debug)]
+pub struct If {
+    span: Span,
+    pub condition: Condition,
+    pub then: Vec<BlockBody>,
+
+    /// An `else if` is an else body containing a single `if`.
+    pub r#else: Option<Vec<BlockBody>>,
+}
+
+/// An `if` condition.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Condition {
+    /// A `Bool` expression.
+    Expr(Expr),
+
+    /// An optional unwrapping, e.g. `let y = x`,
+    /// binding `y` in the then body if `x` is not `nil`.
+    Let(Id, Expr),
+}
+
+impl If {
+    pub fn new(
+        span: Span,
+        condition: Condition,
+        then: Vec<BlockBody>,
+        r#else: Option<Vec<BlockBody>>,
+    ) -> Self {
+        Self {
+            span,
+            condition,
+            then,
+            r#else,
+        }
+    }
+}
+
+impl PartialEq for If {
+    fn eq(&self, other: &Self) -> bool {
+        self.condition == other.condition && self.then == other.then && self.r#else == other.r#else
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Condition::Expr(expr) => write!(f, "{}", expr),
+            Condition::Let(id, expr) => write!(f, "let {} = {}", id, expr),
+        }
+    }
+}
+
+impl Display for If {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "if {} {{}}", self.condition)?;
+
+        if self.r#else.is_some() {
+            write!(f, " else {{}}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl HasSpan for If {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
@@ -2,23 +2,23 @@ use std::fmt::{Display, Formatter};
 
 use crate::location::{HasSpan, Span};
 
-use super::{literal, Id};
+use super::{literal, Id, Qualifier};
 
-/// A `use` node.
+/// An `import` statement, e.g. `import { Foo as Bar } from "foo"`.
 #[derive(Clone, Debug)]
 pub struct Import {
     span: Span,
     pub r#pub: bool,
-    pub ids: Vec<Id>,
+    pub imported: Imported,
     pub from: literal::String,
 }
 
 impl Import {
-    pub fn new(span: Span, r#pub: bool, ids: Vec<Id>, from: literal::String) -> Self {
+    pub fn new(span: Span, r#pub: bool, imported: Imported, from: literal::String) -> Self {
         Self {
             r#pub,
             span,
-            ids,
+            imported,
             from,
         }
     }
@@ -26,22 +26,13 @@ impl Import {
 
 impl PartialEq for Import {
     fn eq(&self, other: &Self) -> bool {
-        self.ids == other.ids && self.from == other.from
+        self.imported == other.imported && self.from == other.from
     }
 }
 
 impl Display for Import {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "import {{{}}} from {}",
-            self.ids
-                .iter()
-                .map(|id| id.to_string())
-                .collect::<Vec<String>>()
-                .join(", "),
-            self.from
-        )
+        write!(f, "import {} from {}", self.imported, self.from)
     }
 }
 
@@ -50,3 +41,71 @@ impl HasSpan for Import {
         self.span
     }
 }
+
+/// What an import brings into scope.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Imported {
+    /// Listed exports, e.g. `{ Foo, Net::Http as Http2 }`.
+    Ids(Vec<ImportId>),
+
+    /// The whole module as a namespace, e.g. `* as net`.
+    Module(Id),
+
+    /// All exports of the module, i.e. `*`.
+    All,
+}
+
+impl Display for Imported {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Imported::Ids(ids) => write!(
+                f,
+                "{{{}}}",
+                ids.iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Imported::Module(id) => write!(f, "* as {}", id),
+            Imported::All => write!(f, "*"),
+        }
+    }
+}
+
+/// An imported export, optionally aliased, e.g. `Foo as Bar`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportId {
+    span: Span,
+    pub qualifier: Qualifier,
+    pub alias: Option<Id>,
+}
+
+impl ImportId {
+    pub fn new(span: Span, qualifier: Qualifier, alias: Option<Id>) -> Self {
+        Self {
+            span,
+            qualifier,
+            alias,
+        }
+    }
+
+    /// Return the id the export is accessible by in the importing module.
+    pub fn id(&self) -> &Id {
+        self.alias.as_ref().unwrap_or(&self.qualifier.id)
+    }
+}
+
+impl Display for ImportId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.alias {
+            Some(alias) => write!(f, "{} as {}", self.qualifier, alias),
+            None => write!(f, "{}", self.qualifier),
+        }
+    }
+}
+
+impl HasSpan for ImportId {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
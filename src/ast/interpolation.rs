@@ -0,0 +1,57 @@
+use super::Expr;
+use crate::location::{HasSpan, Span};
+use std::fmt::{Display, Formatter};
+
+/// A segment of an interpolated string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    /// Literal text, with `{{` and `}}` already unescaped.
+    Text(String),
+
+    /// An empty `{}` placeholder, only valid in format macros.
+    Placeholder(Span),
+
+    /// An interpolated expression, e.g. `{x}`.
+    Expr(Expr),
+}
+
+/// An interpolated string node, e.g. `"value is {x}"`.
+#[derive(Clone, Debug)]
+pub struct Interpolation {
+    span: Span,
+    pub segments: Vec<Segment>,
+}
+
+impl Interpolation {
+    pub fn new(span: Span, segments: Vec<Segment>) -> Self {
+        Self { span, segments }
+    }
+}
+
+impl PartialEq for Interpolation {
+    fn eq(&self, other: &Self) -> bool {
+        self.segments == other.segments
+    }
+}
+
+impl Display for Interpolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"")?;
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(text) => write!(f, "{}", text.replace('{', "{{").replace('}', "}}"))?,
+                Segment::Placeholder(_) => write!(f, "{{}}")?,
+                Segment::Expr(expr) => write!(f, "{{{}}}", expr)?,
+            }
+        }
+
+        write!(f, "\"")
+    }
+}
+
+impl HasSpan for Interpolation {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
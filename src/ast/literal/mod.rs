@@ -3,3 +3,6 @@ pub use string::String;
 
 mod bool;
 pub use self::bool::Bool;
+
+mod nil;
+pub use nil::Nil;
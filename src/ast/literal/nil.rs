@@ -0,0 +1,32 @@
+use crate::location::{HasSpan, Span};
+use std::fmt::{Debug, Display, Formatter};
+
+/// A `nil` literal node, i.e. the absent optional value.
+#[derive(Clone, Debug)]
+pub struct Nil {
+    span: Span,
+}
+
+impl Nil {
+    pub fn new(span: Span) -> Self {
+        Self { span }
+    }
+}
+
+impl PartialEq for Nil {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Display for Nil {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "nil")
+    }
+}
+
+impl HasSpan for Nil {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
@@ -25,7 +25,10 @@ pub use comment::Comment;
 pub mod literal;
 
 mod import;
-pub use import::Import;
+pub use import::{Import, ImportId, Imported};
+
+mod c_import;
+pub use c_import::CImport;
 
 mod decorator;
 pub use decorator::Decorator;
@@ -39,6 +42,32 @@ pub use call::Call;
 mod qualifier;
 pub use qualifier::Qualifier;
 
+mod r#return;
+pub use r#return::Return;
+
+mod r#if;
+pub use r#if::{Condition, If};
+
+mod throw;
+pub use throw::Throw;
+
+mod r#try;
+pub use r#try::{Catch, Try};
+
+pub mod error;
+
+mod namespace;
+pub use namespace::Namespace;
+
+mod type_expr;
+pub use type_expr::TypeExpr;
+
+mod closure;
+pub use closure::{Capture, Closure};
+
+pub mod interpolation;
+pub use interpolation::Interpolation;
+
 use std::fmt::Debug;
 
 /// An ASt module corresponds to a single source file.
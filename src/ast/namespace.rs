@@ -0,0 +1,41 @@
+use super::{BlockBody, Id};
+use crate::location::{HasSpan, Span};
+use std::fmt::{Debug, Display, Formatter};
+
+/// A namespace declaration node, e.g. `namespace Net { }`.
+#[derive(Clone, Debug)]
+pub struct Namespace {
+    span: Span,
+    pub r#pub: bool,
+    pub id: Id,
+    pub body: Vec<BlockBody>,
+}
+
+impl Namespace {
+    pub fn new(span: Span, r#pub: bool, id: Id, body: Vec<BlockBody>) -> Self {
+        Self {
+            span,
+            r#pub,
+            id,
+            body,
+        }
+    }
+}
+
+impl PartialEq for Namespace {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.body == other.body
+    }
+}
+
+impl Display for Namespace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "namespace {} {{}}", self.id)
+    }
+}
+
+impl HasSpan for Namespace {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
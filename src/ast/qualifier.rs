@@ -3,21 +3,26 @@ use std::fmt::Display;
 use super::Id;
 use crate::location::{HasSpan, Span};
 
+/// A possibly multi-segment path, e.g. `Net::Http::get`.
 #[derive(PartialEq, Debug, Clone)]
 pub struct Qualifier {
     span: Span,
-    // TODO: pub container: Option<Box<Qualifier>>,
+    pub container: Option<Box<Qualifier>>,
     // TODO: pub accessor: Option<Accessor>,
     pub id: Id,
 }
 
 impl Qualifier {
-    pub fn new(span: Span, id: Id) -> Self {
-        Self { span, id }
+    pub fn new(span: Span, container: Option<Qualifier>, id: Id) -> Self {
+        Self {
+            span,
+            container: container.map(Box::new),
+            id,
+        }
     }
 
     pub fn from_string(span: Span, id: String) -> Self {
-        Self::new(span, Id::new(span, id))
+        Self::new(span, None, Id::new(span, id))
     }
 }
 
@@ -29,6 +34,10 @@ impl HasSpan for Qualifier {
 
 impl Display for Qualifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(container) = &self.container {
+            write!(f, "{}::", container)?;
+        }
+
         self.id.fmt(f)
     }
 }
@@ -0,0 +1,38 @@
+use super::Expr;
+use crate::location::{HasSpan, Span};
+use std::fmt::{Debug, Display, Formatter};
+
+/// A `return` statement node.
+#[derive(Clone, Debug)]
+pub struct Return {
+    span: Span,
+    pub expr: Option<Expr>,
+}
+
+impl Return {
+    pub fn new(span: Span, expr: Option<Expr>) -> Self {
+        Self { span, expr }
+    }
+}
+
+impl PartialEq for Return {
+    fn eq(&self, other: &Self) -> bool {
+        self.expr == other.expr
+    }
+}
+
+impl Display for Return {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(expr) = &self.expr {
+            write!(f, "return {}", expr)
+        } else {
+            write!(f, "return")
+        }
+    }
+}
+
+impl HasSpan for Return {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
@@ -1,18 +1,28 @@
-use super::{function, r#struct, Decorator, Expr, Import, VarDecl};
+use super::{
+    error, function, r#struct, CImport, Decorator, Expr, If, Import, Namespace, Return, Throw,
+    VarDecl,
+};
 use crate::location::{HasSpan, Span};
 use std::fmt::{Debug, Display};
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum Statement {
     VarDecl(VarDecl),
     TerminatedExpr(Expr),
     Import(Import),
+    CImport(CImport),
 
     // IDEA: An expression may also be decorated?
     Decorator(Decorator),
 
     StructDef(r#struct::Def),
+    StructImpl(r#struct::Impl),
     FunctionDecl(function::Decl),
+    Return(Return),
+    Throw(Throw),
+    If(If),
+    Namespace(Namespace),
+    ErrorDecl(error::Decl),
 }
 
 impl HasSpan for Statement {
@@ -21,9 +31,16 @@ impl HasSpan for Statement {
             Statement::VarDecl(v) => v.span(),
             Statement::TerminatedExpr(e) => e.span(),
             Statement::Import(i) => i.span(),
+            Statement::CImport(i) => i.span(),
             Statement::Decorator(d) => d.span(),
             Statement::StructDef(d) => d.span(),
+            Statement::StructImpl(i) => i.span(),
             Statement::FunctionDecl(d) => d.span(),
+            Statement::Return(r) => r.span(),
+            Statement::Throw(t) => t.span(),
+            Statement::If(i) => i.span(),
+            Statement::ErrorDecl(d) => d.span(),
+            Statement::Namespace(n) => n.span(),
         }
     }
 }
@@ -34,9 +51,16 @@ impl Display for Statement {
             Statement::VarDecl(v) => write!(f, "{}", v),
             Statement::TerminatedExpr(e) => write!(f, "{}", e),
             Statement::Import(i) => write!(f, "{}", i),
+            Statement::CImport(i) => write!(f, "{}", i),
             Statement::Decorator(d) => write!(f, "{}", d),
             Statement::StructDef(d) => write!(f, "{}", d),
+            Statement::StructImpl(i) => write!(f, "{}", i),
             Statement::FunctionDecl(d) => write!(f, "{}", d),
+            Statement::Return(r) => write!(f, "{}", r),
+            Statement::Throw(t) => write!(f, "{}", t),
+            Statement::If(i) => write!(f, "{}", i),
+            Statement::ErrorDecl(d) => write!(f, "{}", d),
+            Statement::Namespace(n) => write!(f, "{}", n),
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    ast::Id,
+    ast::{BlockBody, Id},
     location::{HasSpan, Span},
 };
 use std::fmt::{Debug, Display, Formatter};
@@ -10,24 +10,49 @@ pub struct Def {
     span: Span,
     pub r#pub: bool,
     pub id: Id,
+
+    /// Type parameters of a generic struct, e.g. `T` in `struct Box<T> { }`.
+    pub type_params: Vec<Id>,
+
+    /// Function declarations (and their decorators) within the struct.
+    pub body: Vec<BlockBody>,
 }
 
 impl Def {
     // TODO: Panic if default without export.
-    pub fn new(span: Span, r#pub: bool, id: Id) -> Self {
-        Self { span, r#pub, id }
+    pub fn new(
+        span: Span,
+        r#pub: bool,
+        id: Id,
+        type_params: Vec<Id>,
+        body: Vec<BlockBody>,
+    ) -> Self {
+        Self {
+            span,
+            r#pub,
+            id,
+            type_params,
+            body,
+        }
     }
 }
 
 impl PartialEq for Def {
     fn eq(&self, other: &Self) -> bool {
-        self.id == other.id
+        self.id == other.id && self.type_params == other.type_params && self.body == other.body
     }
 }
 
 impl Display for Def {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "struct {} {{}}", self.id)
+        write!(f, "struct {}", self.id)?;
+
+        if !self.type_params.is_empty() {
+            let params: Vec<String> = self.type_params.iter().map(|p| p.to_string()).collect();
+            write!(f, "<{}>", params.join(", "))?;
+        }
+
+        write!(f, " {{}}")
     }
 }
 
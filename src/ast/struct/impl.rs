@@ -0,0 +1,39 @@
+use crate::{
+    ast::{BlockBody, Qualifier},
+    location::{HasSpan, Span},
+};
+use std::fmt::{Debug, Display, Formatter};
+
+/// A struct implementation node, e.g. `impl Foo { }`.
+#[derive(Clone, Debug)]
+pub struct Impl {
+    span: Span,
+    pub r#type: Qualifier,
+
+    /// Function declarations (and their decorators) within the impl.
+    pub body: Vec<BlockBody>,
+}
+
+impl Impl {
+    pub fn new(span: Span, r#type: Qualifier, body: Vec<BlockBody>) -> Self {
+        Self { span, r#type, body }
+    }
+}
+
+impl PartialEq for Impl {
+    fn eq(&self, other: &Self) -> bool {
+        self.r#type == other.r#type && self.body == other.body
+    }
+}
+
+impl Display for Impl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "impl {} {{}}", self.r#type)
+    }
+}
+
+impl HasSpan for Impl {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
@@ -1,2 +1,5 @@
 mod def;
 pub use def::Def;
+
+mod r#impl;
+pub use r#impl::Impl;
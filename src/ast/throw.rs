@@ -0,0 +1,34 @@
+use super::Qualifier;
+use crate::location::{HasSpan, Span};
+use std::fmt::{Debug, Display, Formatter};
+
+/// A `throw` statement node, e.g. `throw NotFound`.
+#[derive(Clone, Debug)]
+pub struct Throw {
+    span: Span,
+    pub error: Qualifier,
+}
+
+impl Throw {
+    pub fn new(span: Span, error: Qualifier) -> Self {
+        Self { span, error }
+    }
+}
+
+impl PartialEq for Throw {
+    fn eq(&self, other: &Self) -> bool {
+        self.error == other.error
+    }
+}
+
+impl Display for Throw {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "throw {}", self.error)
+    }
+}
+
+impl HasSpan for Throw {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
@@ -0,0 +1,74 @@
+use super::Expr;
+use crate::location::{HasSpan, Span};
+use std::fmt::{Debug, Display, Formatter};
+
+/// A `try` expression node, propagating errors thrown by a call.
+#[derive(Clone, Debug)]
+pub struct Try {
+    span: Span,
+    pub expr: Box<Expr>,
+}
+
+impl Try {
+    pub fn new(span: Span, expr: Expr) -> Self {
+        Self {
+            span,
+            expr: Box::new(expr),
+        }
+    }
+}
+
+impl PartialEq for Try {
+    fn eq(&self, other: &Self) -> bool {
+        self.expr == other.expr
+    }
+}
+
+impl Display for Try {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "try {}", self.expr)
+    }
+}
+
+impl HasSpan for Try {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// A `catch` expression node, e.g. `foo() catch false`,
+/// evaluating to the fallback if the call throws.
+#[derive(Clone, Debug)]
+pub struct Catch {
+    span: Span,
+    pub expr: Box<Expr>,
+    pub fallback: Box<Expr>,
+}
+
+impl Catch {
+    pub fn new(expr: Expr, fallback: Expr) -> Self {
+        Self {
+            span: expr.span().join(fallback.span()),
+            expr: Box::new(expr),
+            fallback: Box::new(fallback),
+        }
+    }
+}
+
+impl PartialEq for Catch {
+    fn eq(&self, other: &Self) -> bool {
+        self.expr == other.expr && self.fallback == other.fallback
+    }
+}
+
+impl Display for Catch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} catch {}", self.expr, self.fallback)
+    }
+}
+
+impl HasSpan for Catch {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
@@ -0,0 +1,107 @@
+use super::Qualifier;
+use crate::location::{HasSpan, Span};
+use std::fmt::Display;
+
+/// A type expression, e.g. `Bool` or `fn(Bool) -> Bool`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum TypeExpr {
+    /// A named type reference.
+    Ref(Qualifier),
+
+    /// A function type, which values are functions or closures.
+    Function {
+        span: Span,
+        params: Vec<TypeExpr>,
+
+        /// `None` means no returned value, i.e. `void`.
+        return_type: Option<Box<TypeExpr>>,
+    },
+
+    /// An optional type, e.g. `Bool?`, which values may be `nil`.
+    Optional { span: Span, inner: Box<TypeExpr> },
+
+    /// A pointer type, e.g. `*CInt`.
+    Pointer { span: Span, inner: Box<TypeExpr> },
+
+    /// A tuple type of two or more elements, e.g. `(Bool, CInt)`.
+    Tuple { span: Span, elements: Vec<TypeExpr> },
+
+    /// A generic struct instance, e.g. `Box<Bool>`.
+    Instance {
+        span: Span,
+        generic: Qualifier,
+        args: Vec<TypeExpr>,
+    },
+}
+
+impl HasSpan for TypeExpr {
+    fn span(&self) -> Span {
+        match self {
+            TypeExpr::Ref(qualifier) => qualifier.span(),
+            TypeExpr::Function { span, .. } => *span,
+            TypeExpr::Optional { span, .. } => *span,
+            TypeExpr::Pointer { span, .. } => *span,
+            TypeExpr::Tuple { span, .. } => *span,
+            TypeExpr::Instance { span, .. } => *span,
+        }
+    }
+}
+
+impl Display for TypeExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeExpr::Ref(qualifier) => write!(f, "{}", qualifier),
+            TypeExpr::Function {
+                params,
+                return_type,
+                ..
+            } => {
+                write!(f, "fn(")?;
+
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", param)?;
+                }
+
+                write!(f, ")")?;
+
+                if let Some(return_type) = return_type {
+                    write!(f, " -> {}", return_type)?;
+                }
+
+                Ok(())
+            }
+            TypeExpr::Optional { inner, .. } => write!(f, "{}?", inner),
+            TypeExpr::Pointer { inner, .. } => write!(f, "*{}", inner),
+            TypeExpr::Tuple { elements, .. } => {
+                write!(f, "(")?;
+
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", element)?;
+                }
+
+                write!(f, ")")
+            }
+            TypeExpr::Instance { generic, args, .. } => {
+                write!(f, "{}<", generic)?;
+
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", arg)?;
+                }
+
+                write!(f, ">")
+            }
+        }
+    }
+}
@@ -5,24 +5,37 @@ use std::fmt::{Debug, Display, Formatter};
 #[derive(Clone, Debug)]
 pub struct VarDecl {
     span: Span,
+
+    /// Whether the module-level variable is exported.
+    pub r#pub: bool,
+
     pub id: Id,
     pub expr: Expr,
 }
 
 impl VarDecl {
-    pub fn new(span: Span, id: Id, expr: Expr) -> Self {
-        Self { span, id, expr }
+    pub fn new(span: Span, r#pub: bool, id: Id, expr: Expr) -> Self {
+        Self {
+            span,
+            r#pub,
+            id,
+            expr,
+        }
     }
 }
 
 impl PartialEq for VarDecl {
     fn eq(&self, other: &Self) -> bool {
-        self.id == other.id && self.expr == other.expr
+        self.r#pub == other.r#pub && self.id == other.id && self.expr == other.expr
     }
 }
 
 impl Display for VarDecl {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.r#pub {
+            write!(f, "pub ")?;
+        }
+
         write!(f, "let {} = {}", self.id, self.expr)
     }
 }
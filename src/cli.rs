@@ -1,7 +1,12 @@
 use std::path::{Path, PathBuf};
 
-use crate::program::Program;
-use clap::{command, Parser};
+use crate::{
+    manifest::{LibKind, Manifest},
+    package,
+    program::{OutputKind, Program},
+    Panic,
+};
+use clap::Parser;
 
 // I want the default command to be `run`.
 
@@ -15,26 +20,47 @@ pub struct Cli {
     #[clap(
         long,
         value_parser,
-        default_value = ".cache",
-        help = "Path to the cache directory"
+        global = true,
+        help = "Path to the cache directory [default: .cache]"
     )]
-    cache: String,
+    cache: Option<String>,
+
+    #[clap(
+        long,
+        value_parser,
+        global = true,
+        help = "Path to the package manifest [default: nx.toml, if exists]"
+    )]
+    manifest: Option<String>,
+
+    #[clap(
+        short = 'I',
+        long,
+        value_parser,
+        global = true,
+        help = "Add a directory to search bare import specifiers in"
+    )]
+    include: Vec<String>,
+
+    #[clap(
+        long = "nx-path",
+        env = "NX_PATH",
+        value_parser,
+        global = true,
+        help = "Directories to search after `--include`, separated as in `PATH`"
+    )]
+    nx_path: Option<std::ffi::OsString>,
 }
 
 #[derive(clap::Subcommand)]
 enum Action {
     #[clap(about = "Run an Onyx program", alias = "r")]
     Run {
-        #[clap(value_parser, help = "Input file path")]
-        input: String,
+        #[clap(value_parser, help = "Input file path [default: the manifest entry]")]
+        input: Option<String>,
 
-        #[clap(
-            long,
-            value_parser,
-            default_value = "zig",
-            help = "Zig executable path"
-        )]
-        zig: String,
+        #[clap(long, value_parser, help = "Zig executable path [default: zig]")]
+        zig: Option<String>,
     },
 
     #[clap(about = "Compile an Onyx program", alias = "c")]
@@ -42,16 +68,26 @@ enum Action {
         #[clap(value_parser, help = "Input file path")]
         input: String,
 
-        #[clap(short, long, value_parser, help = "Output executable path")]
+        #[clap(short, long, value_parser, help = "Output executable or library path")]
         output: Option<String>,
 
         #[clap(
             long,
-            value_parser,
-            default_value = "zig",
-            help = "Zig executable path"
+            value_enum,
+            num_args = 0..=1,
+            default_missing_value = "static",
+            help = "Compile a C library with a header instead of an executable"
         )]
-        zig: String,
+        lib: Option<LibKind>,
+
+        #[clap(long, value_parser, help = "Zig executable path [default: zig]")]
+        zig: Option<String>,
+    },
+
+    #[clap(about = "Build the package described by the manifest", alias = "b")]
+    Build {
+        #[clap(long, value_parser, help = "Zig executable path [default: zig]")]
+        zig: Option<String>,
     },
 }
 
@@ -59,14 +95,57 @@ impl Cli {
     pub fn run() {
         let cli = Cli::parse();
 
-        let result = match cli.action {
-            Action::Run { input, zig } => {
-                let program = Program::new(Path::new(&cli.cache).into());
-                Program::run(program, Path::new(&input).into(), Path::new(&zig).into())
+        if let Err(panic) = cli.execute() {
+            print!("{}", panic);
+            std::process::exit(1);
+        }
+    }
+
+    fn execute(self) -> Result<(), Panic> {
+        let manifest = self.manifest()?;
+
+        let cache_path = match (&self.cache, &manifest) {
+            (Some(cache), _) => PathBuf::from(cache),
+            (None, Some(manifest)) => manifest.cache_path(),
+            (None, None) => PathBuf::from(".cache"),
+        };
+
+        let program = Program::new(cache_path);
+        program.borrow_mut().search_paths = self.search_paths();
+
+        if let Some(manifest) = &manifest {
+            let packages = package::resolve(manifest)?;
+
+            // Running or building records the dependencies, compiling a file doesn't.
+            if matches!(self.action, Action::Run { .. } | Action::Build { .. }) {
+                package::lockfile(&packages).write(&manifest.lock_path())?;
             }
-            Action::Compile { input, output, zig } => {
-                let program = Program::new(Path::new(&cli.cache).into());
 
+            program.borrow_mut().packages = packages;
+            program.borrow_mut().optimize = manifest.build.optimize;
+        }
+
+        match self.action {
+            Action::Run { input, zig } => {
+                let input_path = match (input, &manifest) {
+                    (Some(input), _) => PathBuf::from(input),
+                    (None, Some(manifest)) => manifest.entry_path(),
+                    (None, None) => {
+                        return Err(Panic::new(
+                            format!("No input file given and no {} found", Manifest::FILE_NAME),
+                            None,
+                        ))
+                    }
+                };
+
+                Program::run(program, input_path, Self::zig_path(zig, manifest.as_ref()))
+            }
+            Action::Compile {
+                input,
+                output,
+                lib,
+                zig,
+            } => {
                 let output_path = match output {
                     Some(output) => Path::new(&output).into(),
                     None => {
@@ -76,18 +155,71 @@ impl Cli {
                     }
                 };
 
+                let output_kind = match lib {
+                    None => OutputKind::Executable,
+                    Some(lib) => lib.into(),
+                };
+
                 Program::compile(
                     program,
                     Path::new(&input).into(),
                     output_path,
-                    Path::new(&zig).into(),
+                    output_kind,
+                    Self::zig_path(zig, manifest.as_ref()),
                 )
             }
-        };
+            Action::Build { zig } => {
+                let Some(manifest) = &manifest else {
+                    return Err(Panic::new(
+                        format!("No {} found in the current directory", Manifest::FILE_NAME),
+                        None,
+                    ));
+                };
 
-        if let Err(panic) = result {
-            print!("{}", panic);
-            std::process::exit(1);
+                let output_kind = match manifest.build.lib {
+                    None => OutputKind::Executable,
+                    Some(lib) => lib.into(),
+                };
+
+                Program::compile(
+                    program,
+                    manifest.entry_path(),
+                    manifest.output_path(),
+                    output_kind,
+                    Self::zig_path(zig, Some(manifest)),
+                )
+            }
         }
     }
+
+    /// Load the manifest passed with `--manifest`, or the one in the current directory.
+    fn manifest(&self) -> Result<Option<Manifest>, Panic> {
+        match &self.manifest {
+            Some(path) => Manifest::load(Path::new(path)).map(Some),
+            None => match Manifest::find(Path::new("")) {
+                Some(path) => Manifest::load(&path).map(Some),
+                None => Ok(None),
+            },
+        }
+    }
+
+    /// Return the Zig path given in the command line, or in the manifest, or `zig`.
+    fn zig_path(zig: Option<String>, manifest: Option<&Manifest>) -> PathBuf {
+        match (zig, manifest.and_then(|m| m.build.zig.as_ref())) {
+            (Some(zig), _) => PathBuf::from(zig),
+            (None, Some(zig)) => zig.clone(),
+            (None, None) => PathBuf::from("zig"),
+        }
+    }
+
+    /// Return the module search paths, in order of precedence.
+    fn search_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.include.iter().map(PathBuf::from).collect();
+
+        if let Some(nx_path) = &self.nx_path {
+            paths.extend(std::env::split_paths(nx_path).filter(|p| !p.as_os_str().is_empty()));
+        }
+
+        paths
+    }
 }
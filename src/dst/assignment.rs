@@ -1,10 +1,11 @@
-use super::{r#struct, Expr, InferType, Scope, VarRef};
+use super::{Expr, InferType, Scope, Type};
 use crate::location::{HasSpan, Span};
-use std::{cell::RefCell, rc::Rc};
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct Assignment {
-    pub lhs: VarRef,
+    /// Either a variable or a by-reference capture.
+    pub lhs: Rc<Expr>,
     pub rhs: Rc<Expr>,
 }
 
@@ -15,7 +16,7 @@ impl HasSpan for Assignment {
 }
 
 impl InferType for Assignment {
-    fn infer_type(&self, scope: &dyn Scope) -> Option<Rc<RefCell<r#struct::Decl>>> {
+    fn infer_type(&self, scope: &dyn Scope) -> Type {
         self.lhs.infer_type(scope)
     }
 }
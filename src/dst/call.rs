@@ -1,20 +1,26 @@
-use super::{function, r#struct, Expr, InferType, Scope};
+use super::{error, function, Expr, FunctionType, InferType, Scope, Type};
 use crate::{ast, location::HasSpan};
 use std::{cell::RefCell, rc::Rc};
 
+/// A called entity.
+#[derive(Debug)]
+pub enum Callee {
+    /// A named function, called directly.
+    Function(Rc<RefCell<function::Decl>>),
+
+    /// A function value, e.g. a closure stored in a variable.
+    Value(Rc<Expr>, Rc<FunctionType>),
+}
+
 #[derive(Debug)]
 pub struct Call {
     pub ast_node: ast::Call,
-    pub callee: Rc<RefCell<function::Decl>>,
+    pub callee: Callee,
     pub args: Vec<Rc<Expr>>,
 }
 
 impl Call {
-    pub fn new(
-        ast_node: ast::Call,
-        callee: Rc<RefCell<function::Decl>>,
-        args: Vec<Rc<Expr>>,
-    ) -> Self {
+    pub fn new(ast_node: ast::Call, callee: Callee, args: Vec<Rc<Expr>>) -> Self {
         Self {
             ast_node,
             callee,
@@ -23,9 +29,22 @@ impl Call {
     }
 }
 
+impl Call {
+    /// Errors the call may throw.
+    pub fn throws(&self) -> Vec<Rc<error::Decl>> {
+        match &self.callee {
+            Callee::Function(decl) => decl.borrow().throws.clone(),
+            Callee::Value(_, r#type) => r#type.throws.clone(),
+        }
+    }
+}
+
 impl InferType for Call {
-    fn infer_type(&self, _scope: &dyn Scope) -> Option<Rc<RefCell<r#struct::Decl>>> {
-        self.callee.borrow().return_type.clone()
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        match &self.callee {
+            Callee::Function(decl) => decl.borrow().return_type.clone(),
+            Callee::Value(_, r#type) => r#type.return_type.clone(),
+        }
     }
 }
 
@@ -0,0 +1,31 @@
+use super::{Capture, InferType, Scope, Type};
+use crate::{
+    ast,
+    location::{HasSpan, Span},
+};
+use std::rc::Rc;
+
+/// A reference to a captured variable within a closure body.
+#[derive(Clone, Debug)]
+pub struct CaptureRef {
+    ast_node: ast::Id,
+    pub capture: Rc<Capture>,
+}
+
+impl CaptureRef {
+    pub fn new(ast_node: ast::Id, capture: Rc<Capture>) -> Self {
+        Self { ast_node, capture }
+    }
+}
+
+impl HasSpan for CaptureRef {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
+impl InferType for CaptureRef {
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        self.capture.r#type.clone()
+    }
+}
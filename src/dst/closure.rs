@@ -0,0 +1,49 @@
+use super::{function, FunctionType, InferType, Scope, Statement, Type};
+use crate::{
+    ast,
+    location::{HasSpan, Span},
+};
+use std::rc::Rc;
+
+mod capture;
+pub use capture::Capture;
+
+/// An anonymous function with an explicitly captured environment.
+#[derive(Debug)]
+pub struct Closure {
+    ast_node: ast::Closure,
+    pub captures: Vec<Rc<Capture>>,
+    pub params: Vec<Rc<function::decl::Param>>,
+    pub r#type: Rc<FunctionType>,
+    pub body: Vec<Statement>,
+}
+
+impl Closure {
+    pub fn new(
+        ast_node: ast::Closure,
+        captures: Vec<Rc<Capture>>,
+        params: Vec<Rc<function::decl::Param>>,
+        r#type: Rc<FunctionType>,
+        body: Vec<Statement>,
+    ) -> Self {
+        Self {
+            ast_node,
+            captures,
+            params,
+            r#type,
+            body,
+        }
+    }
+}
+
+impl HasSpan for Closure {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
+impl InferType for Closure {
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        Type::Function(Rc::clone(&self.r#type))
+    }
+}
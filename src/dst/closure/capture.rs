@@ -0,0 +1,63 @@
+use std::cell::Cell;
+
+use crate::{
+    ast,
+    dst::{Exportable, HasId, Type},
+};
+
+/// A variable captured by a closure.
+#[derive(Debug)]
+pub struct Capture {
+    pub id: ast::Id,
+
+    /// Whether the closure refers to the variable itself, rather than a copy.
+    pub by_ref: bool,
+
+    pub r#type: Type,
+
+    /// The captured variable, parameter or outer capture.
+    pub target: Exportable,
+
+    /// Whether the capture refers to a local variable,
+    /// or its value is a closure which does.
+    pub borrows_locals: bool,
+
+    /// Whether the captured value is a closure referring to local variables.
+    pub value_borrows_locals: bool,
+
+    /// Whether the capture is referenced within the closure body.
+    pub used: Cell<bool>,
+}
+
+impl Capture {
+    pub fn new(id: ast::Id, by_ref: bool, r#type: Type, target: Exportable) -> Self {
+        let value_borrows_locals = match &target {
+            Exportable::VarDecl(var) => var.borrows_locals,
+            Exportable::Capture(outer) => outer.value_borrows_locals,
+            _ => false,
+        };
+
+        // A capture by reference refers to the same variable as its target.
+        let borrows_locals = match &target {
+            Exportable::VarDecl(var) => (by_ref && !var.is_global()) || var.borrows_locals,
+            Exportable::Capture(outer) if by_ref => outer.borrows_locals,
+            _ => value_borrows_locals,
+        };
+
+        Self {
+            id,
+            by_ref,
+            r#type,
+            target,
+            borrows_locals,
+            value_borrows_locals,
+            used: Cell::new(false),
+        }
+    }
+}
+
+impl HasId for Capture {
+    fn id(&self) -> ast::Id {
+        self.id.clone()
+    }
+}
@@ -1,4 +1,11 @@
 pub enum Builtin {
     /// The builtin `@[Builtin]` decorator.
     Builtin,
+
+    /// The `@[Extern]` decorator, giving a struct C layout.
+    Extern,
+
+    /// The `@[Export("c_name")]` decorator,
+    /// exporting a function with the C symbol name.
+    Export(String),
 }
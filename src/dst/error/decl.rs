@@ -0,0 +1,63 @@
+use crate::{
+    ast,
+    dst::HasId,
+    location::{HasSpan, Span},
+    unit::Unit,
+};
+use std::{cell::RefCell, fmt::Display, rc::Weak};
+
+/// An error declaration node.
+#[derive(Debug)]
+pub struct Decl {
+    ast_node: ast::error::Decl,
+
+    /// The unit the error is declared in.
+    pub unit: Weak<RefCell<Unit>>,
+
+    /// Ids of the containing namespaces, outermost first.
+    pub namespace: Vec<ast::Id>,
+
+    /// A program-wide unique error name, e.g. ``1a2b3c4d`::Net`::NotFound``.
+    pub symbol: String,
+}
+
+impl Decl {
+    pub fn new(
+        ast_node: ast::error::Decl,
+        unit: Weak<RefCell<Unit>>,
+        namespace: Vec<ast::Id>,
+        symbol: String,
+    ) -> Self {
+        Self {
+            ast_node,
+            unit,
+            namespace,
+            symbol,
+        }
+    }
+}
+
+impl HasId for Decl {
+    fn id(&self) -> ast::Id {
+        self.ast_node.id.clone()
+    }
+}
+
+impl HasSpan for Decl {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
+/// Errors are identified by their symbols.
+impl PartialEq for Decl {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
+}
+
+impl Display for Decl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error {}", self.ast_node.id)
+    }
+}
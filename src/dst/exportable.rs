@@ -1,4 +1,4 @@
-use super::{function, r#struct, HasId, VarDecl};
+use super::{error, function, r#struct, Capture, HasId, Namespace, VarDecl};
 use crate::ast;
 use std::{cell::RefCell, rc::Rc};
 
@@ -8,6 +8,10 @@ pub enum Exportable {
     VarDecl(Rc<VarDecl>),
     StructDecl(Rc<RefCell<r#struct::Decl>>),
     FunctionDecl(Rc<RefCell<function::Decl>>),
+    Param(Rc<function::decl::Param>),
+    Namespace(Rc<RefCell<Namespace>>),
+    Capture(Rc<Capture>),
+    ErrorDecl(Rc<error::Decl>),
 }
 
 impl HasId for Exportable {
@@ -16,6 +20,22 @@ impl HasId for Exportable {
             Exportable::VarDecl(decl) => decl.id(),
             Exportable::StructDecl(decl) => decl.borrow().id(),
             Exportable::FunctionDecl(decl) => decl.borrow().id(),
+            Exportable::Param(param) => param.id(),
+            Exportable::Namespace(namespace) => namespace.borrow().id(),
+            Exportable::Capture(capture) => capture.id(),
+            Exportable::ErrorDecl(decl) => decl.id(),
+        }
+    }
+}
+
+impl Exportable {
+    /// Whether both refer to the same variable, parameter or capture.
+    pub fn ptr_eq(&self, other: &Exportable) -> bool {
+        match (self, other) {
+            (Exportable::VarDecl(a), Exportable::VarDecl(b)) => Rc::ptr_eq(a, b),
+            (Exportable::Param(a), Exportable::Param(b)) => Rc::ptr_eq(a, b),
+            (Exportable::Capture(a), Exportable::Capture(b)) => Rc::ptr_eq(a, b),
+            _ => false,
         }
     }
 }
@@ -1,6 +1,9 @@
-use std::{cell::RefCell, rc::Rc};
+use std::rc::Rc;
 
-use super::{r#struct, Assignment, Call, Exportable, InferType, MacroCall, Scope, VarRef};
+use super::{
+    Assignment, Call, CaptureRef, Catch, Closure, Exportable, FunctionRef, InferType,
+    Interpolation, MacroCall, NilCheck, ParamRef, Scope, Try, Type, Unwrap, VarRef,
+};
 use crate::{
     ast::{self},
     location::{HasSpan, Span},
@@ -9,40 +12,122 @@ use crate::{
 #[derive(Debug)]
 pub enum Expr {
     BoolLiteral(ast::literal::Bool),
+    StringLiteral(ast::literal::String),
+
+    /// A string literal passed where a `CString` is expected,
+    /// which is sound as literals are NUL-terminated.
+    CStringLiteral(ast::literal::String),
+
+    /// A `nil` literal, inferred as an optional `never`.
+    NilLiteral(ast::literal::Nil),
+
+    Interpolation(Interpolation),
     VarRef(VarRef),
+    ParamRef(ParamRef),
+    CaptureRef(CaptureRef),
+    FunctionRef(FunctionRef),
     MacroCall(MacroCall),
     FunctionCall(Call),
     Assignment(Assignment),
+    Closure(Rc<Closure>),
+    Try(Try),
+    Catch(Catch),
+    NilCheck(NilCheck),
+    Unwrap(Unwrap),
+}
+
+impl Expr {
+    /// Whether the evaluation never completes, i.e. it panics.
+    pub fn diverges(&self) -> bool {
+        matches!(
+            self,
+            Expr::MacroCall(
+                MacroCall::Panic(..) | MacroCall::Todo(..) | MacroCall::Unreachable(..)
+            )
+        )
+    }
+
+    /// Whether the value is a closure referring to local variables,
+    /// directly or through its captures, thus it must not outlive them.
+    pub fn borrows_locals(&self) -> bool {
+        match self {
+            Expr::Closure(c) => c.captures.iter().any(|c| c.borrows_locals),
+            Expr::VarRef(r) => r.decl.borrows_locals,
+            Expr::CaptureRef(r) => r.capture.value_borrows_locals,
+            Expr::Unwrap(u) => u.value.borrows_locals(),
+            Expr::Catch(c) => c.fallback.borrows_locals(),
+            _ => false,
+        }
+    }
 }
 
 impl HasSpan for Expr {
     fn span(&self) -> Span {
         match self {
             Expr::BoolLiteral(b) => b.span(),
+            Expr::StringLiteral(s) | Expr::CStringLiteral(s) => s.span(),
+            Expr::NilLiteral(n) => n.span(),
+            Expr::Interpolation(i) => i.span(),
             Expr::VarRef(r) => r.span(),
+            Expr::ParamRef(r) => r.span(),
+            Expr::CaptureRef(r) => r.span(),
+            Expr::FunctionRef(r) => r.span(),
             Expr::MacroCall(m) => m.span(),
             Expr::Assignment(a) => a.span(),
             Expr::FunctionCall(c) => c.span(),
+            Expr::Closure(c) => c.span(),
+            Expr::Try(t) => t.span(),
+            Expr::Catch(c) => c.span(),
+            Expr::NilCheck(c) => c.span(),
+            Expr::Unwrap(u) => u.span(),
         }
     }
 }
 
 impl InferType for Expr {
-    fn infer_type(&self, scope: &dyn Scope) -> Option<Rc<RefCell<r#struct::Decl>>> {
+    fn infer_type(&self, scope: &dyn Scope) -> Type {
         match self {
             Expr::BoolLiteral(_) => {
                 let found = scope.search_builtin(&ast::Id::new(self.span(), "Bool".to_string()));
 
                 if let Some(Exportable::StructDecl(decl)) = found {
-                    Some(decl)
+                    Type::Struct(decl)
                 } else {
                     panic!("`Bool` not found")
                 }
             }
+            Expr::StringLiteral(_) => {
+                let found = scope.search_builtin(&ast::Id::new(self.span(), "String".to_string()));
+
+                if let Some(Exportable::StructDecl(decl)) = found {
+                    Type::Struct(decl)
+                } else {
+                    panic!("`String` not found")
+                }
+            }
+            Expr::CStringLiteral(_) => {
+                let found = scope.search_builtin(&ast::Id::new(self.span(), "CString".to_string()));
+
+                if let Some(Exportable::StructDecl(decl)) = found {
+                    Type::Struct(decl)
+                } else {
+                    panic!("`CString` not found")
+                }
+            }
+            Expr::NilLiteral(_) => Type::Optional(Box::new(Type::Never)),
+            Expr::Interpolation(i) => i.infer_type(scope),
             Expr::VarRef(r) => r.infer_type(scope),
+            Expr::ParamRef(r) => r.infer_type(scope),
+            Expr::CaptureRef(r) => r.infer_type(scope),
+            Expr::FunctionRef(r) => r.infer_type(scope),
             Expr::MacroCall(m) => m.infer_type(scope),
             Expr::Assignment(a) => a.infer_type(scope),
             Expr::FunctionCall(c) => c.infer_type(scope),
+            Expr::Closure(c) => c.infer_type(scope),
+            Expr::Try(t) => t.infer_type(scope),
+            Expr::Catch(c) => c.infer_type(scope),
+            Expr::NilCheck(c) => c.infer_type(scope),
+            Expr::Unwrap(u) => u.infer_type(scope),
         }
     }
 }
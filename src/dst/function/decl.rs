@@ -1,9 +1,14 @@
-use super::Builtin;
+use super::{Builtin, Extern};
 use crate::{
     ast,
-    dst::{r#struct, HasId, HasQualifier},
+    dst::{error, r#struct, FunctionType, HasId, HasQualifier, Statement, Type},
+    location::{HasSpan, Span},
+    unit::Unit,
+};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
 };
-use std::{cell::RefCell, rc::Rc};
 
 mod param;
 pub use param::Param;
@@ -11,25 +16,81 @@ pub use param::Param;
 #[derive(Debug)]
 pub struct Decl {
     ast_node: ast::function::Decl,
+
+    /// The unit the function is declared in.
+    pub unit: Weak<RefCell<Unit>>,
+
+    /// Ids of the containing namespaces, outermost first.
+    pub namespace: Vec<ast::Id>,
+
     pub builtin: Option<Builtin>,
-    pub params: Vec<Param>,
 
-    /// `None` means no returned value, i.e. `void`.
-    pub return_type: Option<Rc<RefCell<r#struct::Decl>>>,
+    /// `Some` means the function is implemented in C.
+    pub r#extern: Option<Extern>,
+
+    pub params: Vec<Rc<Param>>,
+
+    pub return_type: Type,
+
+    /// Errors the function may throw.
+    pub throws: Vec<Rc<error::Decl>>,
+
+    /// The C symbol name the function is exported with, if any.
+    pub export: Option<String>,
+
+    /// The struct the function is attached to, if any.
+    pub owner: Option<Weak<RefCell<r#struct::Decl>>>,
+
+    /// `None` means the function is only declared, but not defined.
+    pub body: Option<Vec<Statement>>,
 }
 
 impl Decl {
     pub fn new(
         ast_node: ast::function::Decl,
+        unit: Weak<RefCell<Unit>>,
+        namespace: Vec<ast::Id>,
         builtin: Option<Builtin>,
-        params: Vec<Param>,
-        return_type: Option<Rc<RefCell<r#struct::Decl>>>,
+        r#extern: Option<Extern>,
+        params: Vec<Rc<Param>>,
+        return_type: Type,
     ) -> Self {
         Self {
             ast_node,
+            unit,
+            namespace,
             builtin,
+            r#extern,
             params,
             return_type,
+            throws: Vec::new(),
+            export: None,
+            owner: None,
+            body: None,
+        }
+    }
+
+    /// Whether the function has a `self` parameter,
+    /// i.e. it may be called on an instance of its type.
+    pub fn is_instance(&self) -> bool {
+        self.params
+            .first()
+            .is_some_and(|param| param.id.value == "self")
+    }
+
+    /// Whether the function accepts C variadic arguments.
+    pub fn is_variadic(&self) -> bool {
+        self.r#extern.is_some_and(|e| e.variadic)
+    }
+
+    /// Return the function signature type.
+    pub fn r#type(&self) -> FunctionType {
+        FunctionType {
+            throws: self.throws.clone(),
+            ..FunctionType::new(
+                self.params.iter().map(|p| p.r#type.clone()).collect(),
+                self.return_type.clone(),
+            )
         }
     }
 }
@@ -40,6 +101,12 @@ impl HasId for Decl {
     }
 }
 
+impl HasSpan for Decl {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
 impl HasQualifier for Decl {
     fn qualifier(&self) -> ast::Qualifier {
         self.ast_node.id.clone()
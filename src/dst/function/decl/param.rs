@@ -1,19 +1,26 @@
-use std::{cell::RefCell, rc::Rc};
+use std::cell::Cell;
 
 use crate::{
     ast,
-    dst::{r#struct, HasId},
+    dst::{HasId, Type},
 };
 
 #[derive(Debug)]
 pub struct Param {
     pub id: ast::Id,
-    pub r#type: Rc<RefCell<r#struct::Decl>>,
+    pub r#type: Type,
+
+    /// Whether the parameter is referenced within the function body.
+    pub used: Cell<bool>,
 }
 
 impl Param {
-    pub fn new(id: ast::Id, r#type: Rc<RefCell<r#struct::Decl>>) -> Self {
-        Self { id, r#type }
+    pub fn new(id: ast::Id, r#type: Type) -> Self {
+        Self {
+            id,
+            r#type,
+            used: Cell::new(false),
+        }
     }
 }
 
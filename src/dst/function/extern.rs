@@ -0,0 +1,6 @@
+/// A C function declaration, i.e. `extern fn`.
+#[derive(Debug, Clone, Copy)]
+pub struct Extern {
+    /// Whether the function accepts C variadic arguments.
+    pub variadic: bool,
+}
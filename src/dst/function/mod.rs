@@ -1,5 +1,8 @@
 mod builtin;
 pub use builtin::Builtin;
 
+mod r#extern;
+pub use r#extern::Extern;
+
 pub mod decl;
 pub use decl::Decl;
@@ -0,0 +1,31 @@
+use super::{function, InferType, Scope, Type};
+use crate::{
+    ast,
+    location::{HasSpan, Span},
+};
+use std::{cell::RefCell, rc::Rc};
+
+/// A named function used as a value.
+#[derive(Clone, Debug)]
+pub struct FunctionRef {
+    ast_node: ast::Qualifier,
+    pub decl: Rc<RefCell<function::Decl>>,
+}
+
+impl FunctionRef {
+    pub fn new(ast_node: ast::Qualifier, decl: Rc<RefCell<function::Decl>>) -> Self {
+        Self { ast_node, decl }
+    }
+}
+
+impl HasSpan for FunctionRef {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
+impl InferType for FunctionRef {
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        Type::Function(Rc::new(self.decl.borrow().r#type()))
+    }
+}
@@ -0,0 +1,55 @@
+use super::{Expr, Statement, VarDecl};
+use crate::{
+    ast,
+    location::{HasSpan, Span},
+};
+use std::rc::Rc;
+
+/// An `if` statement.
+#[derive(Debug)]
+pub struct If {
+    ast_node: ast::If,
+    pub condition: Condition,
+    pub then: Vec<Statement>,
+    pub r#else: Option<Vec<Statement>>,
+}
+
+/// A resolved `if` condition.
+#[derive(Debug)]
+pub enum Condition {
+    /// A `Bool` expression.
+    Expr(Rc<Expr>),
+
+    /// An optional unwrapping, where the variable
+    /// is of the inner type and its expression is optional.
+    Let(Rc<VarDecl>),
+}
+
+impl If {
+    pub fn new(
+        ast_node: ast::If,
+        condition: Condition,
+        then: Vec<Statement>,
+        r#else: Option<Vec<Statement>>,
+    ) -> Self {
+        Self {
+            ast_node,
+            condition,
+            then,
+            r#else,
+        }
+    }
+
+    /// Whether both branches never continue execution.
+    pub fn diverges(&self) -> bool {
+        let diverges = |body: &[Statement]| body.last().is_some_and(|s| s.diverges());
+
+        diverges(&self.then) && self.r#else.as_deref().is_some_and(diverges)
+    }
+}
+
+impl HasSpan for If {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
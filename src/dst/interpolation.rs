@@ -0,0 +1,36 @@
+use super::{Exportable, Format, InferType, Scope, Type};
+use crate::{
+    ast,
+    location::{HasSpan, Span},
+};
+
+/// An interpolated string, e.g. `"value is {x}"`.
+#[derive(Debug)]
+pub struct Interpolation {
+    ast_node: ast::Interpolation,
+    pub format: Format,
+}
+
+impl Interpolation {
+    pub fn new(ast_node: ast::Interpolation, format: Format) -> Self {
+        Self { ast_node, format }
+    }
+}
+
+impl HasSpan for Interpolation {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
+impl InferType for Interpolation {
+    fn infer_type(&self, scope: &dyn Scope) -> Type {
+        let found = scope.search_builtin(&ast::Id::new(self.span(), "String".to_string()));
+
+        if let Some(Exportable::StructDecl(decl)) = found {
+            Type::Struct(decl)
+        } else {
+            panic!("`String` not found")
+        }
+    }
+}
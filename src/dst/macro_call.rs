@@ -1,28 +1,73 @@
-use super::{r#struct, Expr, InferType, Scope};
+use super::{Exportable, Expr, InferType, Scope, Type};
 use crate::{
     ast,
     location::{HasSpan, Span},
 };
-use std::{cell::RefCell, rc::Rc};
+use std::rc::Rc;
+
+mod format;
+pub use format::Format;
+
+mod report;
+pub use report::Report;
 
 /// For now, a macro call is lowered to a specific Zig code.
 #[derive(Debug)]
 pub enum MacroCall {
-    Assert(ast::MacroCall, Rc<Expr>),
+    /// `@assert(condition[, message])`, panicking if the condition is false.
+    Assert(ast::MacroCall, Rc<Expr>, Report),
+
+    /// `@panic(message)`.
+    Panic(ast::MacroCall, Report),
+
+    /// `@todo([message])`.
+    Todo(ast::MacroCall, Report),
+
+    /// `@unreachable([message])`.
+    Unreachable(ast::MacroCall, Report),
+
+    /// `@print(format, args...)`, writing to stdout.
+    Print(ast::MacroCall, Format),
+
+    /// `@println(format, args...)`, writing to stdout with a trailing newline.
+    Println(ast::MacroCall, Format),
+
+    /// `@format(format, args...)`, evaluating to a `String`.
+    Format(ast::MacroCall, Format),
 }
 
 impl HasSpan for MacroCall {
     fn span(&self) -> Span {
         match self {
-            MacroCall::Assert(m, _) => m.span(),
+            MacroCall::Assert(m, ..) => m.span(),
+            MacroCall::Panic(m, _) => m.span(),
+            MacroCall::Todo(m, _) => m.span(),
+            MacroCall::Unreachable(m, _) => m.span(),
+            MacroCall::Print(m, _) => m.span(),
+            MacroCall::Println(m, _) => m.span(),
+            MacroCall::Format(m, _) => m.span(),
         }
     }
 }
 
 impl InferType for MacroCall {
-    fn infer_type(&self, _scope: &dyn Scope) -> Option<Rc<RefCell<r#struct::Decl>>> {
+    fn infer_type(&self, scope: &dyn Scope) -> Type {
         match self {
-            MacroCall::Assert(..) => None,
+            MacroCall::Assert(..) => Type::Void,
+            MacroCall::Panic(..) => Type::Never,
+            MacroCall::Todo(..) => Type::Never,
+            MacroCall::Unreachable(..) => Type::Never,
+            MacroCall::Print(..) => Type::Void,
+            MacroCall::Println(..) => Type::Void,
+            MacroCall::Format(m, _) => {
+                let found = scope.search_builtin(&ast::Id::new(m.span(), "String".to_string()));
+
+                if let Some(Exportable::StructDecl(decl)) = found {
+                    Type::Struct(decl)
+                } else {
+                    panic!("`String` not found")
+                }
+            }
         }
     }
 }
@@ -0,0 +1,21 @@
+use crate::dst::{Expr, Type};
+use std::rc::Rc;
+
+/// A resolved format string with its arguments, e.g. `"x = {}", x`.
+/// The text is split at `{}` placeholders, with `{{` and `}}` unescaped.
+#[derive(Debug)]
+pub struct Format {
+    /// Text segments around the placeholders,
+    /// thus there is always one more segment than arguments.
+    pub segments: Vec<String>,
+
+    /// The arguments with their types, which direct the formatting.
+    pub args: Vec<(Rc<Expr>, Type)>,
+}
+
+impl Format {
+    pub fn new(segments: Vec<String>, args: Vec<(Rc<Expr>, Type)>) -> Self {
+        debug_assert_eq!(segments.len(), args.len() + 1);
+        Self { segments, args }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::dst::Expr;
+use std::rc::Rc;
+
+/// A runtime panic report, e.g. `Assertion failed: `x`: message at foo.nx:1:1..1:11`.
+/// The location is rendered at resolution, for the source is not available at runtime.
+#[derive(Debug)]
+pub struct Report {
+    /// The static report text, e.g. "Assertion failed: `x`".
+    pub text: String,
+
+    /// An optional `String` message appended to the text.
+    pub message: Option<Rc<Expr>>,
+
+    /// The rendered Onyx source location, e.g. `foo.nx:1:1..1:11`.
+    pub location: String,
+}
+
+impl Report {
+    pub fn new(text: String, message: Option<Rc<Expr>>, location: String) -> Self {
+        Self {
+            text,
+            message,
+            location,
+        }
+    }
+}
@@ -1,28 +1,29 @@
 use std::{
     cell::RefCell,
+    path::{Path, PathBuf},
     rc::{Rc, Weak},
 };
 
 mod scope;
-pub use scope::Scope;
+pub use scope::{BlockScope, ClosureScope, FunctionScope, NamespaceScope, Scope, StructScope};
 
 mod var_decl;
 pub use var_decl::VarDecl;
 
-mod builtin_type;
-pub use builtin_type::BuiltinType;
-
 mod expr;
 pub use expr::Expr;
 
 mod var_ref;
 pub use var_ref::VarRef;
 
+mod param_ref;
+pub use param_ref::ParamRef;
+
 mod assignment;
 pub use assignment::Assignment;
 
 mod macro_call;
-pub use macro_call::MacroCall;
+pub use macro_call::{Format, MacroCall, Report};
 
 mod statement;
 pub use statement::Statement;
@@ -35,16 +36,51 @@ pub use import::Import;
 
 pub mod function;
 
+pub mod error;
+
 mod exportable;
 pub use exportable::Exportable;
 
 mod call;
-pub use call::Call;
+pub use call::{Call, Callee};
+
+mod namespace;
+pub use namespace::Namespace;
+
+mod r#type;
+pub use r#type::{FunctionType, Type};
+
+mod closure;
+pub use closure::{Capture, Closure};
+
+mod capture_ref;
+pub use capture_ref::CaptureRef;
+
+mod function_ref;
+pub use function_ref::FunctionRef;
+
+mod interpolation;
+pub use interpolation::Interpolation;
+
+mod r#try;
+pub use r#try::{Catch, Try};
 
-use crate::{ast, location::HasSpan, program::Program, unit::Unit, Location, Panic};
+mod optional;
+pub use optional::{NilCheck, Unwrap};
+
+mod r#if;
+pub use r#if::{Condition, If};
+
+use crate::{
+    ast,
+    location::{HasSpan, Span},
+    program::Program,
+    unit::Unit,
+    Location, Panic,
+};
 
 pub trait InferType {
-    fn infer_type(&self, scope: &dyn Scope) -> Option<Rc<RefCell<r#struct::Decl>>>;
+    fn infer_type(&self, scope: &dyn Scope) -> Type;
 }
 
 pub trait HasId {
@@ -63,8 +99,17 @@ pub struct Mod {
     pub exports: std::collections::HashMap<String, Exportable>,
     pub imports: std::collections::HashMap<String, Exportable>,
 
+    /// Spans of imported ids, by the name they are accessible by.
+    pub import_spans: std::collections::HashMap<String, Span>,
+
     pub decorators_stack: Vec<decorator::Application>,
     pub declarations: std::collections::HashMap<String, Exportable>,
+
+    /// Struct implementations declared in this module.
+    pub impls: Vec<Rc<r#struct::Impl>>,
+
+    /// C headers imported with `@cimport`, in order.
+    pub c_imports: Vec<String>,
 }
 
 impl Mod {
@@ -74,18 +119,43 @@ impl Mod {
             main: Vec::new(),
             exports: std::collections::HashMap::new(),
             imports: std::collections::HashMap::new(),
+            import_spans: std::collections::HashMap::new(),
             decorators_stack: Vec::new(),
             declarations: std::collections::HashMap::new(),
+            impls: Vec::new(),
+            c_imports: Vec::new(),
         }
     }
 
+    /// Make an `export` of a dependency accessible by `id`,
+    /// also re-exporting it if `pub`.
+    pub fn import(&mut self, id: &ast::Id, export: Exportable, r#pub: bool) -> Result<(), Panic> {
+        self.ensure_not_found(id)?;
+
+        if r#pub {
+            self.exports.insert(id.value.clone(), export.clone());
+        }
+
+        self.import_spans.insert(id.value.clone(), id.span());
+        self.imports.insert(id.value.clone(), export);
+
+        Ok(())
+    }
+
+    /// Resolve the unit imported `from` a path relative to this module,
+    /// e.g. `"./foo"`, or a bare specifier looked up in the program
+    /// search paths, e.g. `"collections/list"`. The `.nx` extension
+    /// is implicit, and a directory is resolved to its `index.nx`;
+    /// `builtin` units are virtual.
     pub fn resolve_dependency(
         &mut self,
         from: ast::literal::String,
     ) -> Result<Rc<RefCell<Unit>>, Panic> {
-        let mut path = self.path();
-        path.pop();
-        path.push(from.value.clone());
+        let path = if from.value == "builtin" || from.value.starts_with("builtin/") {
+            PathBuf::from(&from.value)
+        } else {
+            self.find_dependency(&from)?
+        };
 
         if path == self.path() {
             return Err(Panic::new(
@@ -107,9 +177,12 @@ impl Mod {
         if let Some(dependency) = dependency {
             Ok(dependency.upgrade().unwrap())
         } else {
-            let strong = Program::resolve(
-                self_unit.as_ref().borrow().program.upgrade().unwrap(),
+            let program = self_unit.as_ref().borrow().program.upgrade().unwrap();
+
+            let strong = Program::resolve_import(
+                program,
                 path.clone(),
+                Location::new(self.unit(), from.span()),
             )?;
 
             let weak = Rc::downgrade(&strong);
@@ -124,9 +197,67 @@ impl Mod {
         }
     }
 
-    pub fn store(&mut self, entity: Exportable) -> Result<(), Panic> {
-        self.ensure_not_found(&entity.id())?;
-        self.declarations.insert(entity.id().value, entity);
-        Ok(())
+    /// Find the canonical path of an imported file.
+    /// Onyx-panics listing the tried paths if there is none.
+    fn find_dependency(&self, from: &ast::literal::String) -> Result<PathBuf, Panic> {
+        let specifier = Path::new(&from.value);
+
+        let relative = specifier.is_absolute()
+            || from.value.starts_with("./")
+            || from.value.starts_with("../");
+
+        let mut base = self.path();
+        base.pop();
+        base.push(specifier);
+
+        // A bare specifier is looked up next to the importing file first,
+        // then in the package source roots and search paths.
+        let bases = if relative {
+            vec![base]
+        } else {
+            let program = self.unit().borrow().program.upgrade().unwrap();
+            let mut bases = vec![base];
+            bases.extend(program.borrow().search_bases(&self.path(), specifier));
+            bases
+        };
+
+        let candidates: Vec<PathBuf> = bases
+            .into_iter()
+            .flat_map(|base| {
+                if base.extension().is_some_and(|e| e == "nx") {
+                    vec![base]
+                } else {
+                    let mut with_extension = base.clone().into_os_string();
+                    with_extension.push(".nx");
+                    vec![PathBuf::from(with_extension), base.join("index.nx")]
+                }
+            })
+            .collect();
+
+        for candidate in &candidates {
+            if let Some(path) = Program::canonicalize(candidate) {
+                return Ok(path);
+            }
+        }
+
+        let mut panic = Panic::new(
+            format!("Cannot find module {}", from),
+            Some(Location::new(self.unit(), from.span())),
+        );
+
+        for candidate in &candidates {
+            panic.add_note(format!("Tried \"{}\"", candidate.display()), None);
+        }
+
+        if !relative {
+            panic.add_note(
+                "Add a search path with `--include` or `NX_PATH`, or import a relative path, \
+                 e.g. \"./foo\""
+                    .to_string(),
+                None,
+            );
+        }
+
+        Err(panic)
     }
 }
@@ -0,0 +1,60 @@
+use super::{Exportable, HasId, Mod};
+use crate::{ast, unit::Unit};
+use std::{cell::RefCell, collections::HashMap, rc::Weak};
+
+/// A namespace declaration node.
+#[derive(Debug)]
+pub struct Namespace {
+    id: ast::Id,
+
+    /// The unit the namespace is declared in.
+    pub unit: Weak<RefCell<Unit>>,
+
+    /// Ids of the containing namespaces, outermost first.
+    pub namespace: Vec<ast::Id>,
+
+    pub declarations: HashMap<String, Exportable>,
+
+    /// Declarations accessible from outside of the namespace.
+    pub exports: HashMap<String, Exportable>,
+}
+
+impl Namespace {
+    pub fn new(
+        ast_node: ast::Namespace,
+        unit: Weak<RefCell<Unit>>,
+        namespace: Vec<ast::Id>,
+    ) -> Self {
+        Self {
+            id: ast_node.id,
+            unit,
+            namespace,
+            declarations: HashMap::new(),
+            exports: HashMap::new(),
+        }
+    }
+
+    /// A module imported as a namespace, i.e. `import * as id`.
+    pub fn imported(id: ast::Id, module: &Mod) -> Self {
+        Self {
+            id,
+            unit: module.unit.clone(),
+            namespace: Vec::new(),
+            declarations: module.declarations.clone(),
+            exports: module.exports.clone(),
+        }
+    }
+
+    /// Return the path to declarations within self.
+    pub fn path(&self) -> Vec<ast::Id> {
+        let mut path = self.namespace.clone();
+        path.push(self.id());
+        path
+    }
+}
+
+impl HasId for Namespace {
+    fn id(&self) -> ast::Id {
+        self.id.clone()
+    }
+}
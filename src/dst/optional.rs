@@ -0,0 +1,73 @@
+use super::{Expr, InferType, Scope, Type};
+use crate::{
+    ast,
+    location::{HasSpan, Span},
+};
+use std::rc::Rc;
+
+/// A comparison of an optional value with `nil`, e.g. `x != nil`.
+#[derive(Debug)]
+pub struct NilCheck {
+    ast_node: ast::Binop,
+
+    /// The optional value compared with `nil`.
+    pub value: Rc<Expr>,
+
+    /// Whether the check is `== nil`, otherwise `!= nil`.
+    pub is_nil: bool,
+
+    /// The builtin `Bool` type.
+    pub r#type: Type,
+}
+
+impl NilCheck {
+    pub fn new(ast_node: ast::Binop, value: Rc<Expr>, is_nil: bool, r#type: Type) -> Self {
+        Self {
+            ast_node,
+            value,
+            is_nil,
+            r#type,
+        }
+    }
+}
+
+impl HasSpan for NilCheck {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
+impl InferType for NilCheck {
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        self.r#type.clone()
+    }
+}
+
+/// A reference to an optional variable narrowed to its inner type,
+/// e.g. `x` within `if x != nil { }`.
+#[derive(Debug)]
+pub struct Unwrap {
+    /// The reference to the optional variable.
+    pub value: Rc<Expr>,
+
+    /// The inner type of the optional.
+    pub r#type: Type,
+}
+
+impl Unwrap {
+    pub fn new(value: Rc<Expr>, r#type: Type) -> Self {
+        Self { value, r#type }
+    }
+}
+
+impl HasSpan for Unwrap {
+    fn span(&self) -> Span {
+        self.value.span()
+    }
+}
+
+impl InferType for Unwrap {
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        self.r#type.clone()
+    }
+}
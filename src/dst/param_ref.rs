@@ -0,0 +1,31 @@
+use super::{function, InferType, Scope, Type};
+use crate::{
+    ast,
+    location::{HasSpan, Span},
+};
+use std::rc::Rc;
+
+/// A reference to a function parameter.
+#[derive(Clone, Debug)]
+pub struct ParamRef {
+    ast_node: ast::Id,
+    pub param: Rc<function::decl::Param>,
+}
+
+impl ParamRef {
+    pub fn new(ast_node: ast::Id, param: Rc<function::decl::Param>) -> Self {
+        Self { ast_node, param }
+    }
+}
+
+impl HasSpan for ParamRef {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
+impl InferType for ParamRef {
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        self.param.r#type.clone()
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Exportable, HasId};
+use super::{function, r#struct, Capture, Exportable, FunctionType, HasId, Namespace, Type};
 use crate::{
     ast,
     dst::{self},
@@ -7,7 +7,12 @@ use crate::{
     unit::Unit,
     Location, Panic,
 };
-use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 pub trait Scope {
     /// Return path to self.
@@ -22,9 +27,44 @@ pub trait Scope {
     /// Search in self.
     fn search(&self, id: &ast::Id) -> Option<Exportable>;
 
+    /// Declare an entity in self.
+    fn store(&mut self, entity: Exportable) -> Result<(), Panic>;
+
+    /// Make a stored entity accessible from outside of self.
+    fn export(&mut self, entity: Exportable);
+
+    /// Register a struct implementation declared in self.
+    fn store_impl(&mut self, r#impl: Rc<r#struct::Impl>);
+
+    /// Return ids of the namespaces containing self, outermost first.
+    fn namespace(&self) -> Vec<ast::Id> {
+        Vec::new()
+    }
+
     fn push_decorator(&mut self, decorator: dst::decorator::Application);
     fn pop_decorators(&mut self) -> Vec<dst::decorator::Application>;
 
+    /// Return the struct which `Self` refers to, if any.
+    fn enclosing_struct(&self) -> Option<Rc<RefCell<r#struct::Decl>>> {
+        None
+    }
+
+    /// Return the signature of the function or closure
+    /// which body is being resolved, if any.
+    fn enclosing_function_type(&self) -> Option<Rc<FunctionType>> {
+        None
+    }
+
+    /// Return the type an optional variable is narrowed to in self, if any.
+    fn search_narrowed(&self, _found: &Exportable) -> Option<Type> {
+        None
+    }
+
+    /// Onyx-panic if the entity `found` by `id` may not be referenced from self.
+    fn ensure_accessible(&self, _id: &ast::Id, _found: &Exportable) -> Result<(), Panic> {
+        Ok(())
+    }
+
     /// Onyx-panic if `search` returns `Some`.
     fn ensure_not_found(&self, id: &ast::Id) -> Result<(), Panic> {
         if let Some(found) = self.search(id) {
@@ -102,7 +142,7 @@ impl Scope for dst::Mod {
             }
         }
 
-        if self.path() != PathBuf::from("builtin") && !self.path().starts_with("builtin/") {
+        if self.path() != Path::new("builtin") && !self.path().starts_with("builtin/") {
             if let Some(found) = self.search_builtin(id) {
                 println!("Found builtin for {}", id);
                 return Some(found);
@@ -113,6 +153,41 @@ impl Scope for dst::Mod {
         None
     }
 
+    fn store(&mut self, entity: Exportable) -> Result<(), Panic> {
+        self.ensure_not_found(&entity.id())?;
+        self.declarations.insert(entity.id().value, entity);
+        Ok(())
+    }
+
+    /// Points at the import site if `id` is already imported.
+    fn ensure_not_found(&self, id: &ast::Id) -> Result<(), Panic> {
+        let Some(found) = self.search(id) else {
+            return Ok(());
+        };
+
+        let mut panic = Panic::new(
+            format!("{} already declared", id.value),
+            Some(Location::new(self.unit(), id.span())),
+        );
+
+        let (note, span) = match self.import_spans.get(&id.value) {
+            Some(span) => ("Previously imported here", *span),
+            None => ("Previously declared here", found.id().span()),
+        };
+
+        panic.add_note(note.to_string(), Some(Location::new(self.unit(), span)));
+
+        Err(panic)
+    }
+
+    fn export(&mut self, entity: Exportable) {
+        self.exports.insert(entity.id().value, entity);
+    }
+
+    fn store_impl(&mut self, r#impl: Rc<r#struct::Impl>) {
+        self.impls.push(r#impl);
+    }
+
     fn push_decorator(&mut self, decorator: dst::decorator::Application) {
         self.decorators_stack.push(decorator);
     }
@@ -127,3 +202,426 @@ impl Scope for dst::Mod {
         new
     }
 }
+
+/// A struct body scope, where `Self` refers to the struct.
+pub struct StructScope<'a> {
+    pub parent: &'a mut dyn Scope,
+    pub decl: Rc<RefCell<r#struct::Decl>>,
+}
+
+impl<'a> StructScope<'a> {
+    pub fn new(parent: &'a mut dyn Scope, decl: Rc<RefCell<r#struct::Decl>>) -> Self {
+        Self { parent, decl }
+    }
+}
+
+impl Scope for StructScope<'_> {
+    fn path(&self) -> PathBuf {
+        self.parent.path()
+    }
+
+    fn unit(&self) -> Rc<RefCell<Unit>> {
+        self.parent.unit()
+    }
+
+    fn search_builtin(&self, id: &ast::Id) -> Option<Exportable> {
+        self.parent.search_builtin(id)
+    }
+
+    fn search(&self, id: &ast::Id) -> Option<Exportable> {
+        if id.value == "Self" {
+            return Some(Exportable::StructDecl(Rc::clone(&self.decl)));
+        }
+
+        self.parent.search(id)
+    }
+
+    fn store(&mut self, entity: Exportable) -> Result<(), Panic> {
+        self.parent.store(entity)
+    }
+
+    fn export(&mut self, entity: Exportable) {
+        self.parent.export(entity)
+    }
+
+    fn store_impl(&mut self, r#impl: Rc<r#struct::Impl>) {
+        self.parent.store_impl(r#impl)
+    }
+
+    fn namespace(&self) -> Vec<ast::Id> {
+        self.parent.namespace()
+    }
+
+    fn push_decorator(&mut self, decorator: dst::decorator::Application) {
+        self.parent.push_decorator(decorator)
+    }
+
+    fn pop_decorators(&mut self) -> Vec<dst::decorator::Application> {
+        self.parent.pop_decorators()
+    }
+
+    fn enclosing_struct(&self) -> Option<Rc<RefCell<r#struct::Decl>>> {
+        Some(Rc::clone(&self.decl))
+    }
+}
+
+/// A function body scope, where parameters and local variables are declared.
+pub struct FunctionScope<'a> {
+    pub parent: &'a mut dyn Scope,
+    pub decl: Rc<RefCell<function::Decl>>,
+    pub declarations: HashMap<String, Exportable>,
+}
+
+impl<'a> FunctionScope<'a> {
+    pub fn new(parent: &'a mut dyn Scope, decl: Rc<RefCell<function::Decl>>) -> Self {
+        Self {
+            parent,
+            decl,
+            declarations: HashMap::new(),
+        }
+    }
+}
+
+impl Scope for FunctionScope<'_> {
+    fn path(&self) -> PathBuf {
+        self.parent.path()
+    }
+
+    fn unit(&self) -> Rc<RefCell<Unit>> {
+        self.parent.unit()
+    }
+
+    fn search_builtin(&self, id: &ast::Id) -> Option<Exportable> {
+        self.parent.search_builtin(id)
+    }
+
+    fn search(&self, id: &ast::Id) -> Option<Exportable> {
+        if let Some(found) = self.declarations.get(&id.value) {
+            return Some(found.clone());
+        }
+
+        for param in &self.decl.borrow().params {
+            if param.id.value == id.value {
+                return Some(Exportable::Param(Rc::clone(param)));
+            }
+        }
+
+        self.parent.search(id)
+    }
+
+    fn store(&mut self, entity: Exportable) -> Result<(), Panic> {
+        self.ensure_not_found(&entity.id())?;
+        self.declarations.insert(entity.id().value, entity);
+        Ok(())
+    }
+
+    fn export(&mut self, entity: Exportable) {
+        self.parent.export(entity)
+    }
+
+    fn store_impl(&mut self, r#impl: Rc<r#struct::Impl>) {
+        self.parent.store_impl(r#impl)
+    }
+
+    fn namespace(&self) -> Vec<ast::Id> {
+        self.parent.namespace()
+    }
+
+    fn push_decorator(&mut self, decorator: dst::decorator::Application) {
+        self.parent.push_decorator(decorator)
+    }
+
+    fn pop_decorators(&mut self) -> Vec<dst::decorator::Application> {
+        self.parent.pop_decorators()
+    }
+
+    fn enclosing_struct(&self) -> Option<Rc<RefCell<r#struct::Decl>>> {
+        self.parent.enclosing_struct()
+    }
+
+    fn enclosing_function_type(&self) -> Option<Rc<FunctionType>> {
+        Some(Rc::new(self.decl.borrow().r#type()))
+    }
+}
+
+/// A closure body scope, where only the captured outer variables are accessible.
+pub struct ClosureScope<'a> {
+    pub parent: &'a mut dyn Scope,
+    pub captures: Vec<Rc<Capture>>,
+    pub params: Vec<Rc<function::decl::Param>>,
+    pub r#type: Rc<FunctionType>,
+    pub declarations: HashMap<String, Exportable>,
+}
+
+impl<'a> ClosureScope<'a> {
+    pub fn new(
+        parent: &'a mut dyn Scope,
+        captures: Vec<Rc<Capture>>,
+        params: Vec<Rc<function::decl::Param>>,
+        r#type: Rc<FunctionType>,
+    ) -> Self {
+        Self {
+            parent,
+            captures,
+            params,
+            r#type,
+            declarations: HashMap::new(),
+        }
+    }
+
+    /// Search for locals, parameters and captures of the closure.
+    fn search_local(&self, id: &ast::Id) -> Option<Exportable> {
+        if let Some(found) = self.declarations.get(&id.value) {
+            return Some(found.clone());
+        }
+
+        if let Some(param) = self.params.iter().find(|p| p.id.value == id.value) {
+            return Some(Exportable::Param(Rc::clone(param)));
+        }
+
+        self.captures
+            .iter()
+            .find(|c| c.id.value == id.value)
+            .map(|c| Exportable::Capture(Rc::clone(c)))
+    }
+}
+
+impl Scope for ClosureScope<'_> {
+    fn path(&self) -> PathBuf {
+        self.parent.path()
+    }
+
+    fn unit(&self) -> Rc<RefCell<Unit>> {
+        self.parent.unit()
+    }
+
+    fn search_builtin(&self, id: &ast::Id) -> Option<Exportable> {
+        self.parent.search_builtin(id)
+    }
+
+    fn search(&self, id: &ast::Id) -> Option<Exportable> {
+        self.search_local(id).or_else(|| self.parent.search(id))
+    }
+
+    fn store(&mut self, entity: Exportable) -> Result<(), Panic> {
+        self.ensure_not_found(&entity.id())?;
+        self.declarations.insert(entity.id().value, entity);
+        Ok(())
+    }
+
+    fn export(&mut self, entity: Exportable) {
+        self.parent.export(entity)
+    }
+
+    fn store_impl(&mut self, r#impl: Rc<r#struct::Impl>) {
+        self.parent.store_impl(r#impl)
+    }
+
+    fn namespace(&self) -> Vec<ast::Id> {
+        self.parent.namespace()
+    }
+
+    fn push_decorator(&mut self, decorator: dst::decorator::Application) {
+        self.parent.push_decorator(decorator)
+    }
+
+    fn pop_decorators(&mut self) -> Vec<dst::decorator::Application> {
+        self.parent.pop_decorators()
+    }
+
+    fn enclosing_struct(&self) -> Option<Rc<RefCell<r#struct::Decl>>> {
+        self.parent.enclosing_struct()
+    }
+
+    fn enclosing_function_type(&self) -> Option<Rc<FunctionType>> {
+        Some(Rc::clone(&self.r#type))
+    }
+
+    /// Outer local variables must be captured explicitly.
+    fn ensure_accessible(&self, id: &ast::Id, found: &Exportable) -> Result<(), Panic> {
+        if self.search_local(id).is_some() {
+            return Ok(());
+        }
+
+        match found {
+            Exportable::VarDecl(var) if var.is_global() => Ok(()),
+            Exportable::VarDecl(_) | Exportable::Param(_) | Exportable::Capture(_) => {
+                let mut panic = Panic::new(
+                    format!("{} is not captured by the closure", id),
+                    Some(Location::new(self.unit(), id.span())),
+                );
+
+                panic.add_note(
+                    format!(
+                        "Capture it by value (`fn [{0}]`) or by reference (`fn [&{0}]`)",
+                        id.value
+                    ),
+                    None,
+                );
+
+                Err(panic)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A namespace body scope.
+pub struct NamespaceScope<'a> {
+    pub parent: &'a mut dyn Scope,
+    pub namespace: Rc<RefCell<Namespace>>,
+}
+
+impl<'a> NamespaceScope<'a> {
+    pub fn new(parent: &'a mut dyn Scope, namespace: Rc<RefCell<Namespace>>) -> Self {
+        Self { parent, namespace }
+    }
+}
+
+impl Scope for NamespaceScope<'_> {
+    fn path(&self) -> PathBuf {
+        self.parent.path()
+    }
+
+    fn unit(&self) -> Rc<RefCell<Unit>> {
+        self.parent.unit()
+    }
+
+    fn search_builtin(&self, id: &ast::Id) -> Option<Exportable> {
+        self.parent.search_builtin(id)
+    }
+
+    fn search(&self, id: &ast::Id) -> Option<Exportable> {
+        if let Some(found) = self.namespace.borrow().declarations.get(&id.value) {
+            return Some(found.clone());
+        }
+
+        self.parent.search(id)
+    }
+
+    fn store(&mut self, entity: Exportable) -> Result<(), Panic> {
+        self.ensure_not_found(&entity.id())?;
+
+        self.namespace
+            .borrow_mut()
+            .declarations
+            .insert(entity.id().value, entity);
+
+        Ok(())
+    }
+
+    fn export(&mut self, entity: Exportable) {
+        self.namespace
+            .borrow_mut()
+            .exports
+            .insert(entity.id().value, entity);
+    }
+
+    fn store_impl(&mut self, r#impl: Rc<r#struct::Impl>) {
+        self.parent.store_impl(r#impl)
+    }
+
+    fn namespace(&self) -> Vec<ast::Id> {
+        self.namespace.borrow().path()
+    }
+
+    fn push_decorator(&mut self, decorator: dst::decorator::Application) {
+        self.parent.push_decorator(decorator)
+    }
+
+    fn pop_decorators(&mut self) -> Vec<dst::decorator::Application> {
+        self.parent.pop_decorators()
+    }
+}
+
+/// A nested block scope, e.g. an `if` body, where local variables
+/// are declared and optional variables may be narrowed.
+pub struct BlockScope<'a> {
+    pub parent: &'a mut dyn Scope,
+    pub declarations: HashMap<String, Exportable>,
+
+    /// Optional variables known not to be `nil`, with their inner types.
+    pub narrowed: Vec<(Exportable, Type)>,
+}
+
+impl<'a> BlockScope<'a> {
+    pub fn new(parent: &'a mut dyn Scope, narrowed: Vec<(Exportable, Type)>) -> Self {
+        Self {
+            parent,
+            declarations: HashMap::new(),
+            narrowed,
+        }
+    }
+}
+
+impl Scope for BlockScope<'_> {
+    fn path(&self) -> PathBuf {
+        self.parent.path()
+    }
+
+    fn unit(&self) -> Rc<RefCell<Unit>> {
+        self.parent.unit()
+    }
+
+    fn search_builtin(&self, id: &ast::Id) -> Option<Exportable> {
+        self.parent.search_builtin(id)
+    }
+
+    fn search(&self, id: &ast::Id) -> Option<Exportable> {
+        if let Some(found) = self.declarations.get(&id.value) {
+            return Some(found.clone());
+        }
+
+        self.parent.search(id)
+    }
+
+    fn store(&mut self, entity: Exportable) -> Result<(), Panic> {
+        self.ensure_not_found(&entity.id())?;
+        self.declarations.insert(entity.id().value, entity);
+        Ok(())
+    }
+
+    fn export(&mut self, entity: Exportable) {
+        self.parent.export(entity)
+    }
+
+    fn store_impl(&mut self, r#impl: Rc<r#struct::Impl>) {
+        self.parent.store_impl(r#impl)
+    }
+
+    fn namespace(&self) -> Vec<ast::Id> {
+        self.parent.namespace()
+    }
+
+    fn push_decorator(&mut self, decorator: dst::decorator::Application) {
+        self.parent.push_decorator(decorator)
+    }
+
+    fn pop_decorators(&mut self) -> Vec<dst::decorator::Application> {
+        self.parent.pop_decorators()
+    }
+
+    fn enclosing_struct(&self) -> Option<Rc<RefCell<r#struct::Decl>>> {
+        self.parent.enclosing_struct()
+    }
+
+    fn enclosing_function_type(&self) -> Option<Rc<FunctionType>> {
+        self.parent.enclosing_function_type()
+    }
+
+    fn search_narrowed(&self, found: &Exportable) -> Option<Type> {
+        self.narrowed
+            .iter()
+            .find(|(entity, _)| entity.ptr_eq(found))
+            .map(|(_, r#type)| r#type.clone())
+            .or_else(|| self.parent.search_narrowed(found))
+    }
+
+    fn ensure_accessible(&self, id: &ast::Id, found: &Exportable) -> Result<(), Panic> {
+        if self.declarations.contains_key(&id.value) {
+            return Ok(());
+        }
+
+        self.parent.ensure_accessible(id, found)
+    }
+}
@@ -1,14 +1,31 @@
-use super::{r#struct, Expr, InferType, Scope, VarDecl};
-use std::{cell::RefCell, rc::Rc};
+use super::{error, Expr, If, InferType, Scope, Type, VarDecl};
+use std::rc::Rc;
 
+#[derive(Debug)]
 pub enum Statement {
     VarDecl(Rc<VarDecl>),
     TerminatedExpr(Rc<Expr>),
+    Return(Option<Rc<Expr>>),
+    Throw(Rc<error::Decl>),
+    If(Box<If>),
     // StructDecl(Rc<RefCell<r#struct::Decl>) // ?
 }
 
+impl Statement {
+    /// Whether the execution never continues past the statement,
+    /// i.e. it returns, throws or panics.
+    pub fn diverges(&self) -> bool {
+        match self {
+            Statement::Return(_) | Statement::Throw(_) => true,
+            Statement::TerminatedExpr(expr) => expr.diverges(),
+            Statement::If(r#if) => r#if.diverges(),
+            Statement::VarDecl(_) => false,
+        }
+    }
+}
+
 impl InferType for Statement {
-    fn infer_type(&self, _scope: &dyn Scope) -> Option<Rc<RefCell<r#struct::Decl>>> {
-        None
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        Type::Void
     }
 }
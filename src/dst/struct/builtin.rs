@@ -1,4 +1,7 @@
 #[derive(Clone, Copy, Debug)]
 pub enum Builtin {
     Bool,
+    CInt,
+    CString,
+    String,
 }
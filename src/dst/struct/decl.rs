@@ -1,28 +1,114 @@
 use super::{Builtin, Impl};
-use crate::{ast, dst::HasId};
-use std::{fmt::Display, rc::Rc};
+use crate::{
+    ast,
+    dst::{function, HasId},
+    unit::Unit,
+};
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    rc::{Rc, Weak},
+};
 
 /// A struct declaration node.
 #[derive(Debug)]
 pub struct Decl {
     // TODO: May be either `decl` or `def` AST node.
     ast_node: ast::r#struct::Def,
+
+    /// The unit the struct is declared in.
+    pub unit: Weak<RefCell<Unit>>,
+
+    /// Ids of the containing namespaces, outermost first.
+    pub namespace: Vec<ast::Id>,
+
     impls: Vec<Rc<Impl>>,
     pub builtin: Option<Builtin>,
+
+    /// Whether the struct has C layout, i.e. `@[Extern]`.
+    pub r#extern: bool,
 }
 
 impl Decl {
-    pub fn new(ast_node: ast::r#struct::Def, builtin: Option<Builtin>) -> Self {
+    pub fn new(
+        ast_node: ast::r#struct::Def,
+        unit: Weak<RefCell<Unit>>,
+        namespace: Vec<ast::Id>,
+        builtin: Option<Builtin>,
+        r#extern: bool,
+    ) -> Self {
         Self {
             ast_node,
+            unit,
+            namespace,
             impls: Vec::new(),
             builtin,
+            r#extern,
         }
     }
 
     pub fn add_impl(&mut self, r#impl: Rc<Impl>) {
         self.impls.push(r#impl);
     }
+
+    pub fn impls(&self) -> &[Rc<Impl>] {
+        &self.impls
+    }
+
+    /// Type parameters of a generic struct, instantiated by a type expression.
+    pub fn type_params(&self) -> &[ast::Id] {
+        &self.ast_node.type_params
+    }
+
+    pub fn is_generic(&self) -> bool {
+        !self.type_params().is_empty()
+    }
+
+    /// Search for functions declared in the struct impls visible from `unit`.
+    /// An impl is visible if it is declared in the struct unit,
+    /// in `unit` itself, or in a direct dependency of `unit`.
+    /// More than one found function means ambiguity.
+    pub fn search_functions(
+        &self,
+        id: &ast::Id,
+        unit: &Rc<RefCell<Unit>>,
+    ) -> Vec<Rc<RefCell<function::Decl>>> {
+        let from = Rc::downgrade(unit);
+
+        self.impls
+            .iter()
+            .filter(|i| {
+                i.unit.ptr_eq(&self.unit)
+                    || i.unit.ptr_eq(&from)
+                    || unit
+                        .borrow()
+                        .dependencies
+                        .values()
+                        .any(|dep| dep.ptr_eq(&i.unit))
+            })
+            .filter_map(|i| i.search_function(id))
+            .collect()
+    }
+
+    /// The fully qualified struct name, e.g. `builtin/bool::Bool`,
+    /// prefixed with the declaring unit module name.
+    pub fn qualified_name(&self) -> String {
+        let mut name = match self.unit.upgrade() {
+            Some(unit) => unit.borrow().name(),
+            None => String::new(),
+        };
+
+        for id in self
+            .namespace
+            .iter()
+            .chain(std::iter::once(&self.ast_node.id))
+        {
+            name.push_str("::");
+            name.push_str(&id.value);
+        }
+
+        name
+    }
 }
 
 impl HasId for Decl {
@@ -31,14 +117,18 @@ impl HasId for Decl {
     }
 }
 
+/// Structs are nominal, i.e. identified by the declaring unit,
+/// the containing namespaces and the id.
 impl PartialEq for Decl {
     fn eq(&self, other: &Self) -> bool {
-        self.id() == other.id()
+        self.unit.ptr_eq(&other.unit)
+            && self.namespace == other.namespace
+            && self.id() == other.id()
     }
 }
 
 impl Display for Decl {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "struct {}", self.id())
+        write!(f, "struct `{}`", self.qualified_name())
     }
 }
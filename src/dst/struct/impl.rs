@@ -1,14 +1,42 @@
 use super::Decl;
-use std::{cell::RefCell, rc::Weak};
+use crate::{
+    ast,
+    dst::{function, HasId},
+    unit::Unit,
+};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
 
 /// A struct implementation node.
 #[derive(Debug)]
 pub struct Impl {
     pub decl: Weak<RefCell<Decl>>,
+
+    /// The unit the implementation is declared in.
+    pub unit: Weak<RefCell<Unit>>,
+
+    pub functions: Vec<Rc<RefCell<function::Decl>>>,
 }
 
 impl Impl {
-    pub fn new(decl: Weak<RefCell<Decl>>) -> Self {
-        Self { decl }
+    pub fn new(
+        decl: Weak<RefCell<Decl>>,
+        unit: Weak<RefCell<Unit>>,
+        functions: Vec<Rc<RefCell<function::Decl>>>,
+    ) -> Self {
+        Self {
+            decl,
+            unit,
+            functions,
+        }
+    }
+
+    pub fn search_function(&self, id: &ast::Id) -> Option<Rc<RefCell<function::Decl>>> {
+        self.functions
+            .iter()
+            .find(|f| f.borrow().id() == *id)
+            .cloned()
     }
 }
@@ -0,0 +1,66 @@
+use super::{Expr, InferType, Scope, Type};
+use crate::{
+    ast,
+    location::{HasSpan, Span},
+};
+use std::rc::Rc;
+
+/// A `try` expression, propagating errors thrown by the call.
+#[derive(Debug)]
+pub struct Try {
+    ast_node: ast::Try,
+
+    /// The throwing function call.
+    pub call: Rc<Expr>,
+}
+
+impl Try {
+    pub fn new(ast_node: ast::Try, call: Rc<Expr>) -> Self {
+        Self { ast_node, call }
+    }
+}
+
+impl HasSpan for Try {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
+impl InferType for Try {
+    fn infer_type(&self, scope: &dyn Scope) -> Type {
+        self.call.infer_type(scope)
+    }
+}
+
+/// A `catch` expression, evaluating to the fallback if the call throws.
+#[derive(Debug)]
+pub struct Catch {
+    ast_node: ast::Catch,
+
+    /// The throwing function call.
+    pub call: Rc<Expr>,
+
+    pub fallback: Rc<Expr>,
+}
+
+impl Catch {
+    pub fn new(ast_node: ast::Catch, call: Rc<Expr>, fallback: Rc<Expr>) -> Self {
+        Self {
+            ast_node,
+            call,
+            fallback,
+        }
+    }
+}
+
+impl HasSpan for Catch {
+    fn span(&self) -> Span {
+        self.ast_node.span()
+    }
+}
+
+impl InferType for Catch {
+    fn infer_type(&self, scope: &dyn Scope) -> Type {
+        self.call.infer_type(scope)
+    }
+}
@@ -0,0 +1,257 @@
+use super::{error, r#struct, HasId};
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+/// A resolved type.
+#[derive(Clone, Debug)]
+pub enum Type {
+    /// No value, e.g. a function returning nothing.
+    Void,
+
+    /// The type of an expression which never completes, e.g. `@panic()`.
+    /// It may be passed where any other type is expected.
+    Never,
+
+    /// A nominal struct instance.
+    Struct(Rc<RefCell<r#struct::Decl>>),
+
+    /// A function value, i.e. a named function or a closure.
+    Function(Rc<FunctionType>),
+
+    /// A pointer to a value, e.g. `*CInt`.
+    Pointer(Box<Type>),
+
+    /// An optional value, which may be `nil`.
+    /// The `nil` literal itself is an optional `never`.
+    Optional(Box<Type>),
+
+    /// A fixed-size heterogeneous sequence of values, e.g. `(Bool, CInt)`.
+    Tuple(Vec<Type>),
+
+    /// A generic struct instantiated with type arguments, e.g. `Box<Bool>`.
+    /// Instances of the same struct with equal arguments are the same type.
+    Instance(Rc<RefCell<r#struct::Decl>>, Vec<Type>),
+}
+
+impl Type {
+    /// Whether the type is `void`.
+    pub fn is_void(&self) -> bool {
+        matches!(self, Type::Void)
+    }
+
+    /// Whether the type is `never`.
+    pub fn is_never(&self) -> bool {
+        matches!(self, Type::Never)
+    }
+
+    /// Return the struct declaration if the type is a struct.
+    pub fn as_struct(&self) -> Option<&Rc<RefCell<r#struct::Decl>>> {
+        match self {
+            Type::Struct(decl) => Some(decl),
+            _ => None,
+        }
+    }
+
+    /// Whether a value of the type may cross the C boundary.
+    /// `void` is only allowed as a return type.
+    pub fn is_ffi_safe(&self) -> bool {
+        match self {
+            Type::Void => true,
+            Type::Struct(decl) => {
+                let decl = decl.borrow();
+                matches!(
+                    decl.builtin,
+                    Some(
+                        r#struct::Builtin::Bool
+                            | r#struct::Builtin::CInt
+                            | r#struct::Builtin::CString
+                    )
+                ) || decl.r#extern
+            }
+            Type::Pointer(inner) => inner.is_ffi_safe(),
+            Type::Never
+            | Type::Function(_)
+            | Type::Optional(_)
+            | Type::Tuple(_)
+            | Type::Instance(..) => false,
+        }
+    }
+
+    /// Return the inner type if the type is optional.
+    pub fn as_optional(&self) -> Option<&Type> {
+        match self {
+            Type::Optional(inner) => Some(inner),
+            _ => None,
+        }
+    }
+
+    /// Whether a value of type `other` may be passed where self is expected,
+    /// i.e. the types are equal, `other` is `never`,
+    /// or self is an optional accepting `other` or its inner type.
+    pub fn accepts(&self, other: &Type) -> bool {
+        if self == other || other.is_never() {
+            return true;
+        }
+
+        match (self, other) {
+            (Type::Optional(a), Type::Optional(b)) => a.accepts(b),
+            (Type::Optional(inner), _) => inner.accepts(other),
+            _ => false,
+        }
+    }
+
+    /// Whether the type is the builtin `Bool`.
+    pub fn is_bool(&self) -> bool {
+        matches!(
+            self,
+            Type::Struct(decl) if matches!(decl.borrow().builtin, Some(r#struct::Builtin::Bool))
+        )
+    }
+
+    /// Whether the type is the builtin `String`.
+    pub fn is_string(&self) -> bool {
+        matches!(
+            self,
+            Type::Struct(decl) if matches!(decl.borrow().builtin, Some(r#struct::Builtin::String))
+        )
+    }
+
+    /// Whether the type is the builtin `CString`.
+    pub fn is_c_string(&self) -> bool {
+        matches!(
+            self,
+            Type::Struct(decl) if matches!(decl.borrow().builtin, Some(r#struct::Builtin::CString))
+        )
+    }
+
+    /// Write the type name without the kind prefix,
+    /// e.g. `` `builtin/bool::Bool` ``.
+    fn fmt_name(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Void => write!(f, "void"),
+            Type::Never => write!(f, "never"),
+            Type::Struct(decl) => write!(f, "`{}`", decl.borrow().qualified_name()),
+            Type::Function(function) => write!(f, "{}", function),
+            Type::Pointer(inner) => {
+                write!(f, "*")?;
+                inner.fmt_name(f)
+            }
+            Type::Optional(inner) => {
+                inner.fmt_name(f)?;
+                write!(f, "?")
+            }
+            Type::Tuple(elements) => {
+                write!(f, "(")?;
+
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    element.fmt_name(f)?;
+                }
+
+                write!(f, ")")
+            }
+            Type::Instance(decl, args) => {
+                write!(f, "`{}`<", decl.borrow().qualified_name())?;
+
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    arg.fmt_name(f)?;
+                }
+
+                write!(f, ">")
+            }
+        }
+    }
+}
+
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Type::Void, Type::Void) | (Type::Never, Type::Never) => true,
+            (Type::Struct(a), Type::Struct(b)) => a == b,
+            (Type::Function(a), Type::Function(b)) => a == b,
+            (Type::Pointer(a), Type::Pointer(b)) => a == b,
+            (Type::Optional(a), Type::Optional(b)) => a == b,
+            (Type::Tuple(a), Type::Tuple(b)) => a == b,
+            (Type::Instance(a, a_args), Type::Instance(b, b_args)) => a == b && a_args == b_args,
+            _ => false,
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Void | Type::Never | Type::Function(_) => self.fmt_name(f),
+            Type::Struct(decl) => write!(f, "{}", decl.borrow()),
+            Type::Pointer(_) => {
+                write!(f, "pointer ")?;
+                self.fmt_name(f)
+            }
+            Type::Optional(_) => {
+                write!(f, "optional ")?;
+                self.fmt_name(f)
+            }
+            Type::Tuple(_) => {
+                write!(f, "tuple ")?;
+                self.fmt_name(f)
+            }
+            Type::Instance(..) => {
+                write!(f, "struct ")?;
+                self.fmt_name(f)
+            }
+        }
+    }
+}
+
+/// A function signature type, e.g. `fn(Bool) -> Bool`.
+#[derive(Debug, PartialEq)]
+pub struct FunctionType {
+    pub params: Vec<Type>,
+    pub return_type: Type,
+
+    /// Errors a function of the type may throw.
+    pub throws: Vec<Rc<error::Decl>>,
+}
+
+impl FunctionType {
+    pub fn new(params: Vec<Type>, return_type: Type) -> Self {
+        Self {
+            params,
+            return_type,
+            throws: Vec::new(),
+        }
+    }
+}
+
+impl Display for FunctionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fn(")?;
+
+        for (i, param) in self.params.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            param.fmt_name(f)?;
+        }
+
+        write!(f, ")")?;
+
+        if !self.return_type.is_void() {
+            write!(f, " -> ")?;
+            self.return_type.fmt_name(f)?;
+        }
+
+        for (i, error) in self.throws.iter().enumerate() {
+            write!(f, "{}{}", if i > 0 { ", " } else { " throws " }, error.id())?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,29 +1,49 @@
-use super::{r#struct, Expr, HasId, InferType, Scope};
+use super::{Expr, HasId, InferType, Scope, Type};
 use crate::{
     ast,
     location::{HasSpan, Span},
+    unit::Unit,
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::{Rc, Weak},
 };
-use std::{cell::RefCell, rc::Rc};
 
 #[derive(Debug)]
 pub struct VarDecl {
     ast_node: ast::VarDecl,
-    pub r#type: Rc<RefCell<r#struct::Decl>>,
+    pub r#type: Type,
     pub expr: Rc<Expr>,
+
+    /// The declaring unit of a module-level variable, `None` for locals.
+    pub unit: Option<Weak<RefCell<Unit>>>,
+
+    /// Whether a closure captures the variable by reference,
+    /// thus any call may change it, and it is never narrowed.
+    pub captured_by_ref: Cell<bool>,
+
+    /// Whether the value is a closure referring to local variables,
+    /// see [`Expr::borrows_locals`].
+    pub borrows_locals: bool,
 }
 
 impl VarDecl {
-    pub fn new(
-        ast_node: ast::VarDecl,
-        r#type: Rc<RefCell<r#struct::Decl>>,
-        expr: Rc<Expr>,
-    ) -> Self {
+    pub fn new(ast_node: ast::VarDecl, r#type: Type, expr: Rc<Expr>) -> Self {
         Self {
             ast_node,
             r#type,
+            borrows_locals: expr.borrows_locals(),
             expr,
+            unit: None,
+            captured_by_ref: Cell::new(false),
         }
     }
+
+    /// Whether the variable is declared at the module level,
+    /// thus accessible from functions and other units.
+    pub fn is_global(&self) -> bool {
+        self.unit.is_some()
+    }
 }
 
 impl HasId for VarDecl {
@@ -39,7 +59,7 @@ impl HasSpan for VarDecl {
 }
 
 impl InferType for VarDecl {
-    fn infer_type(&self, _scope: &dyn Scope) -> Option<Rc<RefCell<r#struct::Decl>>> {
-        None
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        Type::Void
     }
 }
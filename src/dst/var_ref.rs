@@ -1,9 +1,9 @@
-use super::{r#struct, InferType, Scope, VarDecl};
+use super::{InferType, Scope, Type, VarDecl};
 use crate::{
     ast,
     location::{HasSpan, Span},
 };
-use std::{cell::RefCell, rc::Rc};
+use std::rc::Rc;
 
 #[derive(Clone, Debug)]
 pub struct VarRef {
@@ -24,7 +24,7 @@ impl HasSpan for VarRef {
 }
 
 impl InferType for VarRef {
-    fn infer_type(&self, _scope: &dyn Scope) -> Option<Rc<RefCell<r#struct::Decl>>> {
-        Some(self.decl.r#type.clone())
+    fn infer_type(&self, _scope: &dyn Scope) -> Type {
+        self.decl.r#type.clone()
     }
 }
@@ -0,0 +1,182 @@
+use crate::{
+    dst::{self, HasId},
+    unit::Unit,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{self, Write},
+    rc::Rc,
+};
+
+/// Write a C header declaring extern structs and exported functions of `units`.
+/// `guard` is the include guard macro name.
+pub fn write(units: &[Rc<RefCell<Unit>>], guard: &str, w: &mut dyn Write) -> io::Result<()> {
+    let mut structs = Vec::new();
+    let mut functions = Vec::new();
+
+    for unit in units {
+        let unit = unit.borrow();
+        let dst = unit.dst.as_ref().expect("Unit must be resolved");
+        collect(&dst.declarations, &mut structs, &mut functions);
+
+        for r#impl in dst.impls.iter() {
+            functions.extend(
+                r#impl
+                    .functions
+                    .iter()
+                    .filter(|f| f.borrow().export.is_some())
+                    .cloned(),
+            );
+        }
+    }
+
+    structs.sort_by_key(|s| s.borrow().id().value);
+    functions.sort_by_key(|f| f.borrow().export.clone());
+
+    writeln!(w, "#ifndef {}", guard)?;
+    writeln!(w, "#define {}", guard)?;
+    writeln!(w)?;
+    writeln!(w, "#include <stdbool.h>")?;
+
+    if !structs.is_empty() {
+        writeln!(w)?;
+    }
+
+    for r#struct in structs {
+        let id = r#struct.borrow().id().value;
+        writeln!(w, "typedef struct {} {{ }} {};", id, id)?;
+    }
+
+    if !functions.is_empty() {
+        writeln!(w)?;
+    }
+
+    for function in functions {
+        let function = function.borrow();
+        write_type(&function.return_type, w)?;
+        write!(w, " {}(", function.export.as_ref().unwrap())?;
+
+        if function.params.is_empty() {
+            write!(w, "void")?;
+        }
+
+        for (i, param) in function.params.iter().enumerate() {
+            if i > 0 {
+                write!(w, ", ")?;
+            }
+
+            // Onyx ids may not be valid C ids, thus params are unnamed.
+            write_type(&param.r#type, w)?;
+        }
+
+        writeln!(w, ");")?;
+    }
+
+    writeln!(w)?;
+    writeln!(w, "#endif")
+}
+
+/// Collect extern structs and exported functions, including namespaced ones.
+fn collect(
+    declarations: &HashMap<String, dst::Exportable>,
+    structs: &mut Vec<Rc<RefCell<dst::r#struct::Decl>>>,
+    functions: &mut Vec<Rc<RefCell<dst::function::Decl>>>,
+) {
+    for decl in declarations.values() {
+        match decl {
+            dst::Exportable::StructDecl(decl) if decl.borrow().r#extern => {
+                structs.push(decl.clone())
+            }
+            dst::Exportable::FunctionDecl(decl) if decl.borrow().export.is_some() => {
+                functions.push(decl.clone())
+            }
+            dst::Exportable::Namespace(namespace) => {
+                collect(&namespace.borrow().declarations, structs, functions)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Write the C spelling of an FFI-safe type.
+fn write_type(r#type: &dst::Type, w: &mut dyn Write) -> io::Result<()> {
+    match r#type {
+        dst::Type::Void => write!(w, "void"),
+        dst::Type::Struct(decl) => {
+            let decl = decl.borrow();
+
+            match decl.builtin {
+                Some(dst::r#struct::Builtin::Bool) => write!(w, "bool"),
+                Some(dst::r#struct::Builtin::CInt) => write!(w, "int"),
+                Some(dst::r#struct::Builtin::CString) => write!(w, "const char*"),
+                Some(dst::r#struct::Builtin::String) => unreachable!("Strings are not FFI-safe"),
+                None => write!(w, "{}", decl.id().value),
+            }
+        }
+        dst::Type::Pointer(inner) => {
+            write_type(inner, w)?;
+            write!(w, "*")
+        }
+        dst::Type::Never
+        | dst::Type::Function(_)
+        | dst::Type::Optional(_)
+        | dst::Type::Tuple(_)
+        | dst::Type::Instance(..) => unreachable!("The type {} is not FFI-safe", r#type),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{program::Program, unit::Unit};
+    use std::rc::Rc;
+
+    fn assert_header(input: &str, expected: &str) {
+        let ast_module = crate::parser::parse_simple(input);
+        let program = Program::new(".cache".into());
+        let unit = Unit::with_source(Rc::downgrade(&program), "<test>".into(), input.to_string());
+        let dst_module = ast_module
+            .resolve(Rc::downgrade(&unit))
+            .expect("Failed to resolve");
+        unit.borrow_mut().dst = Some(dst_module);
+        let mut buf = Vec::<u8>::new();
+        super::write(&[unit], "TEST_H", &mut buf).expect("Failed to write");
+        assert_eq!(String::from_utf8(buf).unwrap(), expected);
+    }
+
+    #[test]
+    pub fn test_header() {
+        assert_header(
+            r#"
+@[Extern]
+struct Handle { }
+
+@[Export("nx_valid")]
+fn valid?(handle: Handle, flag: Bool) -> Bool { return flag }
+
+@[Export("nx_noop")]
+fn noop() { }
+
+@[Export("nx_log")]
+fn log(message: CString) { }
+
+@[Export("nx_reset")]
+fn reset(handle: *Handle) { }
+            "#,
+            r#"#ifndef TEST_H
+#define TEST_H
+
+#include <stdbool.h>
+
+typedef struct Handle { } Handle;
+
+void nx_log(const char*);
+void nx_noop(void);
+void nx_reset(Handle*);
+bool nx_valid(Handle, bool);
+
+#endif
+"#,
+        );
+    }
+}
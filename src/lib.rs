@@ -1,8 +1,11 @@
 pub mod ast;
 pub mod cli;
 pub mod dst;
+pub mod header;
 pub mod location;
 pub mod lower;
+pub mod manifest;
+pub mod package;
 pub mod panic;
 pub mod parser;
 pub mod program;
@@ -1,157 +1,1066 @@
-use crate::dst::{self, HasId};
-use std::io::{self, Write};
+use crate::{
+    ast,
+    dst::{self, HasId},
+    location::HasSpan,
+    unit::Unit,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{self, Write},
+    rc::{Rc, Weak},
+};
+
+/// A lowering context.
+pub struct Context {
+    /// The unit being lowered.
+    pub unit: Weak<RefCell<Unit>>,
+
+    /// The closure which body is being lowered, if any.
+    closure: Option<ClosureNames>,
+
+    /// Whether the unit imports C headers,
+    /// in which case extern functions are taken from them.
+    c_import: bool,
+}
+
+impl Context {
+    pub fn new(unit: Weak<RefCell<Unit>>) -> Self {
+        Self {
+            unit,
+            closure: None,
+            c_import: false,
+        }
+    }
+
+    /// Return a context to lower a closure body within.
+    fn with_closure(&self, closure: ClosureNames) -> Self {
+        Self {
+            unit: self.unit.clone(),
+            closure: Some(closure),
+            c_import: self.c_import,
+        }
+    }
+
+    /// Return a context to lower a unit importing C headers within.
+    fn with_c_import(&self) -> Self {
+        Self {
+            unit: self.unit.clone(),
+            closure: self.closure.clone(),
+            c_import: true,
+        }
+    }
+
+    /// Write a prefix to access a declaration from `unit`,
+    /// unless it is the unit being lowered.
+    fn write_unit_prefix(&self, w: &mut dyn Write, unit: &Weak<RefCell<Unit>>) -> io::Result<()> {
+        if !unit.ptr_eq(&self.unit) {
+            let hash = unit.upgrade().unwrap().as_ref().borrow().hash();
+            write!(w, "@import(\"{}.zig\").", hash)?;
+        }
+
+        Ok(())
+    }
+}
 
 pub trait Lowerable {
-    fn lower(&self, w: &mut dyn Write) -> io::Result<()>;
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()>;
 }
 
-impl Lowerable for dst::Mod {
-    fn lower(&self, w: &mut dyn Write) -> io::Result<()> {
-        for decl in self.declarations.iter() {
-            match decl.1 {
-                dst::Exportable::StructDecl(decl) => {
-                    if let Some(builtin) = decl.as_ref().borrow().builtin {
-                        match builtin {
-                            dst::r#struct::Builtin::Bool => {
-                                // Do not write anything, use `bool`.
-                            }
+/// A Zig identifier, written as `@"..."` with its value escaped.
+/// Onyx ids (including wrapped ones) are always lowered this way.
+struct ZigId<'a>(&'a str);
+
+impl std::fmt::Display for ZigId<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@{}", ZigString(self.0))
+    }
+}
+
+/// A Zig string literal, written as `"..."` with its value escaped.
+struct ZigString<'a>(&'a str);
+
+impl std::fmt::Display for ZigString<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"")?;
+
+        for c in self.0.chars() {
+            match c {
+                '"' => write!(f, "\\\"")?,
+                '\\' => write!(f, "\\\\")?,
+                '\n' => write!(f, "\\n")?,
+                '\r' => write!(f, "\\r")?,
+                '\t' => write!(f, "\\t")?,
+                c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+                c => write!(f, "{}", c)?,
+            }
+        }
+
+        write!(f, "\"")
+    }
+}
+
+/// Zig names of a lowered closure internals, unique per closure.
+/// Each name contains a backtick, which is not allowed in Onyx ids,
+/// so that the names never clash with lowered Onyx ids.
+#[derive(Clone)]
+struct ClosureNames {
+    /// The closure block label.
+    label: String,
+
+    /// The captured environment struct.
+    env_type: String,
+
+    /// The environment pointer, passed to the call function.
+    ctx: String,
+
+    /// The environment, available within the closure body.
+    env: String,
+}
+
+impl ClosureNames {
+    fn new(closure: &dst::Closure) -> Self {
+        let offset = closure.span().start.offset;
+
+        Self {
+            label: format!("closure{}", offset),
+            env_type: ZigId(&format!("`Env{}", offset)).to_string(),
+            ctx: ZigId(&format!("`ctx{}", offset)).to_string(),
+            env: ZigId(&format!("`env{}", offset)).to_string(),
+        }
+    }
+}
+
+/// The name of a function value call function.
+const CALL: &str = "@\"`call\"";
+
+/// The runtime support file name, written next to lowered units.
+pub const RUNTIME: &str = "runtime.zig";
+
+/// The runtime support source.
+pub const RUNTIME_SOURCE: &str = include_str!("lower/runtime.zig");
+
+/// The separator of mangled name segments, e.g. ``Net`::get``.
+/// No identifier contains a backtick, even if wrapped, thus names never collide.
+pub const MANGLE_SEPARATOR: &str = "`::";
+
+/// Mangle a declaration id with the ids of its containing namespaces.
+fn mangle(namespace: &[ast::Id], id: &ast::Id) -> String {
+    namespace
+        .iter()
+        .chain(std::iter::once(id))
+        .map(|id| id.value.as_str())
+        .collect::<Vec<_>>()
+        .join(MANGLE_SEPARATOR)
+}
+
+/// Lower module or namespace declarations, sorted by their ids.
+/// Namespaced declarations are flattened with mangled names.
+fn lower_declarations(
+    declarations: &HashMap<String, dst::Exportable>,
+    w: &mut dyn Write,
+    ctx: &Context,
+) -> io::Result<()> {
+    let mut declarations: Vec<_> = declarations.iter().collect();
+    declarations.sort_by(|a, b| a.0.cmp(b.0));
+
+    for decl in declarations {
+        match decl.1 {
+            dst::Exportable::StructDecl(decl) => {
+                let decl = decl.as_ref().borrow();
+
+                if let Some(builtin) = decl.builtin {
+                    match builtin {
+                        dst::r#struct::Builtin::Bool
+                        | dst::r#struct::Builtin::CInt
+                        | dst::r#struct::Builtin::CString
+                        | dst::r#struct::Builtin::String => {
+                            // Do not write anything, use the Zig primitive type.
+                        }
+                    }
+                } else if decl.is_generic() {
+                    // A generic struct is a function returning the instance type.
+                    write!(w, "pub fn {}(", ZigId(&mangle(&decl.namespace, &decl.id())))?;
+
+                    for (i, param) in decl.type_params().iter().enumerate() {
+                        if i > 0 {
+                            write!(w, ", ")?;
                         }
-                    } else {
-                        unimplemented!("Lowering non-builtin structs")
+
+                        write!(w, "comptime {}: type", ZigId(&param.value))?;
+                    }
+
+                    writeln!(w, ") type {{")?;
+
+                    for param in decl.type_params() {
+                        writeln!(w, "_ = {};", ZigId(&param.value))?;
                     }
+
+                    writeln!(w, "return struct {{}};")?;
+                    writeln!(w, "}}")?;
+                } else {
+                    writeln!(
+                        w,
+                        "pub const {} = {}struct {{}};",
+                        ZigId(&mangle(&decl.namespace, &decl.id())),
+                        if decl.r#extern { "extern " } else { "" }
+                    )?;
                 }
-                dst::Exportable::VarDecl(_) => {
-                    // Currently variables are only declared for main.
+            }
+            dst::Exportable::VarDecl(var) => {
+                // Initialized by the unit init function, in statement order.
+                write!(w, "pub var {}: ", ZigId(&var_name(var)))?;
+                var.r#type.lower(w, ctx)?;
+                writeln!(w, " = undefined;")?;
+            }
+            dst::Exportable::FunctionDecl(decl) => {
+                if decl.as_ref().borrow().builtin.is_none() {
+                    decl.as_ref().borrow().lower(w, ctx)?;
                 }
-                dst::Exportable::FunctionDecl(_) => {
-                    // Function declarations aren't lowered.
+            }
+            dst::Exportable::Namespace(namespace) => {
+                lower_declarations(&namespace.as_ref().borrow().declarations, w, ctx)?;
+            }
+            dst::Exportable::ErrorDecl(_) => {
+                // Do not write anything, errors are declared by error sets.
+            }
+            dst::Exportable::Param(_) => unreachable!("Params are not declared in modules"),
+            dst::Exportable::Capture(_) => unreachable!("Captures are not declared in modules"),
+        }
+    }
+
+    Ok(())
+}
+
+/// The Zig name of the imported C headers namespace.
+const C_IMPORT: ZigId = ZigId("`c");
+
+/// The Zig name of a unit initialization function, running its top-level statements.
+const INIT: ZigId = ZigId("`init");
+
+impl Lowerable for dst::Mod {
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        let c_import_ctx;
+        let ctx = if self.c_imports.is_empty() {
+            ctx
+        } else {
+            writeln!(w, "const {} = @cImport({{", C_IMPORT)?;
+
+            for header in self.c_imports.iter() {
+                writeln!(w, "@cInclude({});", ZigString(header))?;
+            }
+
+            writeln!(w, "}});")?;
+            c_import_ctx = ctx.with_c_import();
+            &c_import_ctx
+        };
+
+        lower_declarations(&self.declarations, w, ctx)?;
+
+        for r#impl in self.impls.iter() {
+            for function in r#impl.functions.iter() {
+                if function.as_ref().borrow().builtin.is_none() {
+                    function.as_ref().borrow().lower(w, ctx)?;
                 }
             }
         }
 
-        for import in self.imports.iter() {
-            match &import.1 {
-                dst::Exportable::StructDecl(decl) => {
-                    if let Some(builtin) = decl.as_ref().borrow().builtin {
-                        match builtin {
-                            dst::r#struct::Builtin::Bool => {
-                                // Do not import anything, use `bool`.
-                            }
-                        }
-                    } else {
-                        unimplemented!("Lowering non-builtin structs")
-                    }
+        // Imported entities are referenced from their units directly.
+
+        // Top-level statements are run by the entry point, see `write_entry_point`.
+        writeln!(w, "pub fn {}() void {{", INIT)?;
+        lower_block(&self.main, w, ctx)?;
+        writeln!(w, "}}")
+    }
+}
+
+/// Write the program entry point, which initializes units
+/// by their lowered file hashes in order, and then the entry unit.
+/// Finally, it frees the runtime, including closure environments.
+pub fn write_entry_point(w: &mut dyn Write, init_order: &[String]) -> io::Result<()> {
+    writeln!(w, "pub fn main() void {{")?;
+
+    for hash in init_order {
+        writeln!(w, "@import(\"{}.zig\").{}();", hash, INIT)?;
+    }
+
+    writeln!(w, "{}();", INIT)?;
+    writeln!(w, "@import(\"{}\").deinit();", RUNTIME)?;
+    writeln!(w, "}}")
+}
+
+impl Lowerable for dst::r#struct::Decl {
+    /// Lower a reference to the struct type.
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        if let Some(builtin) = self.builtin {
+            match builtin {
+                dst::r#struct::Builtin::Bool => write!(w, "bool"),
+                dst::r#struct::Builtin::CInt => write!(w, "c_int"),
+                dst::r#struct::Builtin::CString => write!(w, "[*c]const u8"),
+                dst::r#struct::Builtin::String => write!(w, "[]const u8"),
+            }
+        } else {
+            ctx.write_unit_prefix(w, &self.unit)?;
+            write!(w, "{}", ZigId(&mangle(&self.namespace, &self.id())))
+        }
+    }
+}
+
+/// Write the lowered function name, mangled with its owner struct name.
+/// An extern or exported function name is its C symbol, thus it is never mangled.
+fn lower_function_name(decl: &dst::function::Decl, w: &mut dyn Write) -> io::Result<()> {
+    if let Some(export) = &decl.export {
+        write!(w, "{}", ZigId(export))
+    } else if decl.r#extern.is_some() {
+        write!(w, "{}", ZigId(&decl.id().value))
+    } else if let Some(owner) = &decl.owner {
+        let owner = owner.upgrade().unwrap();
+        let owner = owner.borrow();
+        let owner = mangle(&owner.namespace, &owner.id());
+        write!(
+            w,
+            "{}",
+            ZigId(&format!("{}{}{}", owner, MANGLE_SEPARATOR, decl.id().value))
+        )
+    } else {
+        write!(w, "{}", ZigId(&mangle(&decl.namespace, &decl.id())))
+    }
+}
+
+impl Lowerable for dst::function::Decl {
+    /// Lower a function definition, or a C function declaration if extern.
+    /// With C headers imported, an extern function is taken from the headers.
+    /// An exported function is defined with the C calling convention.
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        if let Some(r#extern) = self.r#extern.filter(|_| ctx.c_import) {
+            // Coercing the imported C function to the declared type
+            // checks the declaration against the headers.
+            write!(w, "pub const ")?;
+            lower_function_name(self, w)?;
+            write!(w, ": fn (")?;
+
+            for (i, param) in self.params.iter().enumerate() {
+                if i > 0 {
+                    write!(w, ", ")?;
+                }
+
+                param.r#type.lower(w, ctx)?;
+            }
+
+            if r#extern.variadic {
+                if !self.params.is_empty() {
+                    write!(w, ", ")?;
                 }
-                dst::Exportable::VarDecl(_) => {
-                    unimplemented!()
+
+                write!(w, "...")?;
+            }
+
+            write!(w, ") callconv(.C) ")?;
+            self.return_type.lower(w, ctx)?;
+            write!(w, " = {}.", C_IMPORT)?;
+            lower_function_name(self, w)?;
+            return writeln!(w, ";");
+        }
+
+        if let Some(r#extern) = self.r#extern {
+            write!(w, "pub extern \"c\" fn ")?;
+            lower_function_name(self, w)?;
+            write!(w, "(")?;
+
+            for (i, param) in self.params.iter().enumerate() {
+                if i > 0 {
+                    write!(w, ", ")?;
                 }
-                dst::Exportable::FunctionDecl(decl) => {
-                    if decl.as_ref().borrow().builtin.is_some() {
-                        // Do not import builtin function declarations.
-                    } else {
-                        unimplemented!("Lowering non-builtin structs")
-                    }
+
+                write!(w, "{}: ", ZigId(&param.id.value))?;
+                param.r#type.lower(w, ctx)?;
+            }
+
+            if r#extern.variadic {
+                if !self.params.is_empty() {
+                    write!(w, ", ")?;
                 }
+
+                write!(w, "...")?;
             }
+
+            write!(w, ") ")?;
+            self.return_type.lower(w, ctx)?;
+            return writeln!(w, ";");
+        }
+
+        if self.export.is_some() {
+            write!(w, "pub export fn ")?;
+        } else {
+            write!(w, "pub fn ")?;
         }
 
-        writeln!(w, "pub fn main() void {{")?;
+        lower_function_name(self, w)?;
+        write!(w, "(")?;
 
-        // TODO: Call `main` from dependencies.
+        for (i, param) in self.params.iter().enumerate() {
+            if i > 0 {
+                write!(w, ", ")?;
+            }
 
-        for stmt in self.main.iter() {
-            stmt.lower(w)?;
-            writeln!(w)?;
+            write!(w, "{}: ", ZigId(&param.id.value))?;
+            param.r#type.lower(w, ctx)?;
         }
 
+        write!(w, ") ")?;
+        lower_error_set(&self.throws, w)?;
+        self.return_type.lower(w, ctx)?;
+        writeln!(w, " {{")?;
+        lower_unused_params(&self.params, w)?;
+
+        lower_block(
+            self.body.as_ref().expect("Function must be defined"),
+            w,
+            ctx,
+        )?;
+
         writeln!(w, "}}")
     }
 }
 
+/// Lower the error set of a throwing function, e.g. ``error{@"a`::Foo"}!``.
+/// Writes nothing if the function never throws.
+fn lower_error_set(throws: &[Rc<dst::error::Decl>], w: &mut dyn Write) -> io::Result<()> {
+    if throws.is_empty() {
+        return Ok(());
+    }
+
+    write!(w, "error{{")?;
+
+    for (i, error) in throws.iter().enumerate() {
+        if i > 0 {
+            write!(w, ", ")?;
+        }
+
+        write!(w, "{}", ZigId(&error.symbol))?;
+    }
+
+    write!(w, "}}!")
+}
+
+/// Zig does not allow unused parameters.
+fn lower_unused_params(
+    params: &[Rc<dst::function::decl::Param>],
+    w: &mut dyn Write,
+) -> io::Result<()> {
+    for param in params.iter().filter(|p| !p.used.get()) {
+        writeln!(w, "_ = {};", ZigId(&param.id.value))?;
+    }
+
+    Ok(())
+}
+
+impl Lowerable for dst::Type {
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        match self {
+            dst::Type::Void => write!(w, "void"),
+            dst::Type::Never => write!(w, "noreturn"),
+            dst::Type::Struct(decl) => decl.borrow().lower(w, ctx),
+            dst::Type::Function(function) => function.lower(w, ctx),
+            dst::Type::Pointer(inner) => {
+                write!(w, "*")?;
+                inner.lower(w, ctx)
+            }
+            dst::Type::Optional(inner) => {
+                write!(w, "?")?;
+                inner.lower(w, ctx)
+            }
+            // `std.meta.Tuple` is memoized, unlike anonymous struct types.
+            dst::Type::Tuple(elements) => {
+                write!(w, "@import(\"std\").meta.Tuple(&.{{ ")?;
+
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(w, ", ")?;
+                    }
+
+                    element.lower(w, ctx)?;
+                }
+
+                write!(w, " }})")
+            }
+            // Zig memoizes generic function calls, thus equal instances are the same type.
+            dst::Type::Instance(decl, args) => {
+                decl.borrow().lower(w, ctx)?;
+                write!(w, "(")?;
+
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(w, ", ")?;
+                    }
+
+                    arg.lower(w, ctx)?;
+                }
+
+                write!(w, ")")
+            }
+        }
+    }
+}
+
+impl Lowerable for dst::FunctionType {
+    /// Lower a function value type, which is a tuple of an optional
+    /// environment pointer and a call function, which Zig 0.9
+    /// represents as a runtime function pointer.
+    /// `std.meta.Tuple` is memoized, thus the same signature
+    /// lowers to the same Zig type in every unit.
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        write!(
+            w,
+            "@import(\"std\").meta.Tuple(&.{{ ?*const anyopaque, fn (?*const anyopaque"
+        )?;
+
+        for param in &self.params {
+            write!(w, ", ")?;
+            param.lower(w, ctx)?;
+        }
+
+        write!(w, ") ")?;
+        self.return_type.lower(w, ctx)?;
+        write!(w, " }})")
+    }
+}
+
+impl Lowerable for ZigId<'_> {
+    fn lower(&self, w: &mut dyn Write, _ctx: &Context) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
+}
+
+/// Lower a direct call of a named function.
+fn lower_function_call(
+    callee: &dst::function::Decl,
+    args: &[&dyn Lowerable],
+    w: &mut dyn Write,
+    ctx: &Context,
+) -> io::Result<()> {
+    if let Some(builtin) = &callee.builtin {
+        match builtin {
+            dst::function::Builtin::BoolEq => {
+                args[0].lower(w, ctx)?;
+                write!(w, " == ")?;
+                args[1].lower(w, ctx)
+            }
+        }
+    } else {
+        ctx.write_unit_prefix(w, &callee.unit)?;
+        lower_function_name(callee, w)?;
+        write!(w, "(")?;
+
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                write!(w, ", ")?;
+            }
+
+            arg.lower(w, ctx)?;
+        }
+
+        write!(w, ")")
+    }
+}
+
+impl Lowerable for dst::FunctionRef {
+    /// Lower a named function value, which calls the function
+    /// from a thunk ignoring the environment pointer.
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        let decl = self.decl.borrow();
+
+        write!(w, "@as(")?;
+        decl.r#type().lower(w, ctx)?;
+        write!(w, ", .{{ null, struct {{ fn {}(_: ?*const anyopaque", CALL)?;
+
+        let names: Vec<String> = (0..decl.params.len())
+            .map(|i| ZigId(&format!("`{}", i)).to_string())
+            .collect();
+
+        for (name, param) in names.iter().zip(decl.params.iter()) {
+            write!(w, ", {}: ", name)?;
+            param.r#type.lower(w, ctx)?;
+        }
+
+        write!(w, ") ")?;
+        decl.return_type.lower(w, ctx)?;
+        write!(w, " {{ return ")?;
+
+        let args: Vec<RawZig> = names.into_iter().map(RawZig).collect();
+        let args: Vec<&dyn Lowerable> = args.iter().map(|a| a as &dyn Lowerable).collect();
+        lower_function_call(&decl, &args, w, ctx)?;
+
+        write!(w, "; }} }}.{} }})", CALL)
+    }
+}
+
+/// A C variadic argument, promoted as C does, e.g. `bool` to `c_int`.
+struct VariadicArg<'a>(&'a dst::Expr);
+
+impl Lowerable for VariadicArg<'_> {
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        write!(w, "@import(\"{}\").promote(", RUNTIME)?;
+        self.0.lower(w, ctx)?;
+        write!(w, ")")
+    }
+}
+
+/// Already lowered Zig code.
+struct RawZig(String);
+
+impl Lowerable for RawZig {
+    fn lower(&self, w: &mut dyn Write, _ctx: &Context) -> io::Result<()> {
+        write!(w, "{}", self.0)
+    }
+}
+
+impl Lowerable for dst::Closure {
+    /// Lower a closure to a block, which allocates the captured environment
+    /// struct and evaluates to a function value calling the struct call function.
+    ///
+    /// Function values may be copied freely, thus an environment has no single
+    /// owner to free it. Environments are allocated from the runtime closure arena
+    /// instead, which the entry point frees once the program finishes.
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        let names = ClosureNames::new(self);
+        let body_ctx = ctx.with_closure(names.clone());
+
+        writeln!(w, "{}: {{", names.label)?;
+        writeln!(w, "const {} = struct {{", names.env_type)?;
+
+        for capture in &self.captures {
+            write!(w, "{}: ", ZigId(&capture.id.value))?;
+
+            if capture.by_ref {
+                write!(w, "*")?;
+            }
+
+            capture.r#type.lower(w, ctx)?;
+            writeln!(w, ",")?;
+        }
+
+        write!(w, "fn {}({}: ?*const anyopaque", CALL, names.ctx)?;
+
+        for param in &self.params {
+            write!(w, ", {}: ", ZigId(&param.id.value))?;
+            param.r#type.lower(w, ctx)?;
+        }
+
+        write!(w, ") ")?;
+        self.r#type.return_type.lower(w, ctx)?;
+        writeln!(w, " {{")?;
+
+        if self.captures.iter().any(|c| c.used.get()) {
+            writeln!(
+                w,
+                "const {}: *const @This() = @ptrCast(*const @This(), @alignCast(@alignOf(@This()), {}.?));",
+                names.env, names.ctx
+            )?;
+        } else {
+            writeln!(w, "_ = {};", names.ctx)?;
+        }
+
+        lower_unused_params(&self.params, w)?;
+
+        lower_block(&self.body, w, &body_ctx)?;
+
+        writeln!(w, "}}")?;
+        writeln!(w, "}};")?;
+
+        let env = if self.captures.is_empty() {
+            "null".to_string()
+        } else {
+            writeln!(
+                w,
+                "const {} = @import(\"{}\").closures.allocator().create({}) catch @panic(\"Out of memory\");",
+                names.env, RUNTIME, names.env_type
+            )?;
+
+            write!(w, "{}.* = .{{ ", names.env)?;
+
+            for capture in &self.captures {
+                write!(w, ".{} = ", ZigId(&capture.id.value))?;
+
+                if capture.by_ref {
+                    if let dst::Exportable::VarDecl(_) = capture.target {
+                        write!(w, "&")?;
+                    }
+                }
+
+                match &capture.target {
+                    dst::Exportable::VarDecl(var) => {
+                        if let Some(unit) = &var.unit {
+                            ctx.write_unit_prefix(w, unit)?;
+                        }
+
+                        write!(w, "{}", ZigId(&var_name(var)))?
+                    }
+                    dst::Exportable::Param(param) => write!(w, "{}", ZigId(&param.id.value))?,
+                    dst::Exportable::Capture(outer) => {
+                        let outer_names = ctx.closure.as_ref().expect("Must be within a closure");
+                        write!(w, "{}.{}", outer_names.env, ZigId(&outer.id.value))?;
+
+                        if outer.by_ref && !capture.by_ref {
+                            write!(w, ".*")?;
+                        }
+                    }
+                    _ => unreachable!("Only variables may be captured"),
+                }
+
+                write!(w, ", ")?;
+            }
+
+            writeln!(w, "}};")?;
+            names.env.clone()
+        };
+
+        write!(w, "break :{} @as(", names.label)?;
+        self.r#type.lower(w, ctx)?;
+        write!(w, ", .{{ {}, {}.{} }});\n}}", env, names.env_type, CALL)
+    }
+}
+
 impl Lowerable for dst::Statement {
-    fn lower(&self, w: &mut dyn Write) -> io::Result<()> {
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
         match self {
             dst::Statement::VarDecl(var) => {
-                var.lower(w)?;
+                var.lower(w, ctx)?;
             }
             dst::Statement::TerminatedExpr(expr) => {
-                expr.lower(w)?;
+                expr.lower(w, ctx)?;
             }
+            dst::Statement::Return(expr) => {
+                write!(w, "return")?;
+
+                if let Some(expr) = expr {
+                    write!(w, " ")?;
+                    expr.lower(w, ctx)?;
+                }
+            }
+            dst::Statement::Throw(error) => {
+                write!(w, "return error.{}", ZigId(&error.symbol))?;
+            }
+            dst::Statement::If(r#if) => return r#if.lower(w, ctx),
         }
 
         write!(w, ";")
     }
 }
 
+impl Lowerable for dst::If {
+    /// An `if let` is lowered to a Zig payload capture,
+    /// copied to a variable to keep it mutable.
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        write!(w, "if (")?;
+
+        match &self.condition {
+            dst::Condition::Expr(expr) => {
+                expr.lower(w, ctx)?;
+                writeln!(w, ") {{")?;
+            }
+            dst::Condition::Let(var) => {
+                let id = var.id().value;
+                let payload = ZigId(&format!("`{}", id)).to_string();
+
+                var.expr.lower(w, ctx)?;
+                writeln!(w, ") |{}| {{", payload)?;
+                writeln!(w, "var {} = {};", ZigId(&id), payload)?;
+            }
+        }
+
+        lower_block(&self.then, w, ctx)?;
+        write!(w, "}}")?;
+
+        if let Some(r#else) = &self.r#else {
+            writeln!(w, " else {{")?;
+            lower_block(r#else, w, ctx)?;
+            write!(w, "}}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lower statements, each on its own line.
+fn lower_block(statements: &[dst::Statement], w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+    for stmt in statements {
+        stmt.lower(w, ctx)?;
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
+/// Return the Zig name of a variable.
+/// Module-level variables are container-level declarations,
+/// thus mangled so that they don't clash with locals, which Zig forbids.
+fn var_name(var: &dst::VarDecl) -> String {
+    if var.is_global() {
+        format!("`let {}", var.id().value)
+    } else {
+        var.id().value
+    }
+}
+
 impl Lowerable for dst::VarRef {
-    fn lower(&self, w: &mut dyn Write) -> io::Result<()> {
-        write!(w, "@\"{}\"", self.decl.id().value)
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        if let Some(unit) = &self.decl.unit {
+            ctx.write_unit_prefix(w, unit)?;
+        }
+
+        write!(w, "{}", ZigId(&var_name(&self.decl)))
+    }
+}
+
+impl Lowerable for dst::ParamRef {
+    fn lower(&self, w: &mut dyn Write, _ctx: &Context) -> io::Result<()> {
+        write!(w, "{}", ZigId(&self.param.id.value))
+    }
+}
+
+impl Lowerable for dst::CaptureRef {
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        let names = ctx.closure.as_ref().expect("Must be within a closure");
+        write!(w, "{}.{}", names.env, ZigId(&self.capture.id.value))?;
+
+        if self.capture.by_ref {
+            write!(w, ".*")?;
+        }
+
+        Ok(())
     }
 }
 
 impl Lowerable for dst::Expr {
-    fn lower(&self, w: &mut dyn Write) -> io::Result<()> {
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
         match self {
             dst::Expr::BoolLiteral(b) => write!(w, "{}", b.value),
-            dst::Expr::VarRef(var) => var.lower(w),
-            dst::Expr::MacroCall(m) => m.lower(w),
+            dst::Expr::StringLiteral(s) => write!(w, "@as([]const u8, {})", ZigString(&s.value)),
+            dst::Expr::CStringLiteral(s) => write!(w, "@as([*c]const u8, {})", ZigString(&s.value)),
+            dst::Expr::NilLiteral(_) => write!(w, "null"),
+            dst::Expr::Interpolation(i) => lower_alloc_format(&i.format, w, ctx),
+            dst::Expr::VarRef(var) => var.lower(w, ctx),
+            dst::Expr::ParamRef(param) => param.lower(w, ctx),
+            dst::Expr::CaptureRef(capture) => capture.lower(w, ctx),
+            dst::Expr::FunctionRef(function) => function.lower(w, ctx),
+            dst::Expr::Closure(closure) => closure.lower(w, ctx),
+            dst::Expr::MacroCall(m) => m.lower(w, ctx),
             dst::Expr::Assignment(a) => {
-                a.lhs.lower(w)?;
+                a.lhs.lower(w, ctx)?;
                 write!(w, " = ")?;
-                a.rhs.lower(w)
+                a.rhs.lower(w, ctx)
+            }
+            dst::Expr::FunctionCall(c) => c.lower(w, ctx),
+            dst::Expr::Try(t) => {
+                write!(w, "try ")?;
+                t.call.lower(w, ctx)
+            }
+            dst::Expr::Catch(c) => {
+                write!(w, "(")?;
+                c.call.lower(w, ctx)?;
+                write!(w, " catch ")?;
+                c.fallback.lower(w, ctx)?;
+                write!(w, ")")
+            }
+            dst::Expr::NilCheck(c) => {
+                write!(w, "(")?;
+                c.value.lower(w, ctx)?;
+                write!(w, " {} null)", if c.is_nil { "==" } else { "!=" })
+            }
+            dst::Expr::Unwrap(u) => {
+                u.value.lower(w, ctx)?;
+                write!(w, ".?")
+            }
+        }
+    }
+}
+
+impl Lowerable for dst::Call {
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        match &self.callee {
+            dst::Callee::Function(callee) => {
+                let params_len = callee.borrow().params.len();
+
+                let variadic: Vec<VariadicArg> = self.args[params_len..]
+                    .iter()
+                    .map(|a| VariadicArg(a))
+                    .collect();
+
+                let args: Vec<&dyn Lowerable> = self
+                    .args
+                    .iter()
+                    .take(params_len)
+                    .map(|a| a.as_ref() as &dyn Lowerable)
+                    .chain(variadic.iter().map(|a| a as &dyn Lowerable))
+                    .collect();
+
+                lower_function_call(&callee.borrow(), &args, w, ctx)
+            }
+            dst::Callee::Value(callee, _) => {
+                callee.lower(w, ctx)?;
+                write!(w, "[1](")?;
+                callee.lower(w, ctx)?;
+                write!(w, "[0]")?;
+
+                for arg in &self.args {
+                    write!(w, ", ")?;
+                    arg.lower(w, ctx)?;
+                }
+
+                write!(w, ")")
+            }
+        }
+    }
+}
+
+impl Lowerable for dst::VarDecl {
+    /// Lower a local variable declaration,
+    /// or a module-level variable initialization.
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        if self.is_global() {
+            write!(w, "{} = ", ZigId(&var_name(self)))?;
+        } else {
+            write!(w, "var {} = ", ZigId(&self.id().value))?;
+        }
+
+        self.expr.lower(w, ctx)?;
+        Ok(())
+    }
+}
+
+impl Lowerable for dst::MacroCall {
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        match self {
+            dst::MacroCall::Assert(_, expr, report) => {
+                write!(w, "if (!(")?;
+                expr.lower(w, ctx)?;
+                write!(w, ")) ")?;
+                report.lower(w, ctx)
+            }
+            dst::MacroCall::Panic(_, report)
+            | dst::MacroCall::Todo(_, report)
+            | dst::MacroCall::Unreachable(_, report) => report.lower(w, ctx),
+            dst::MacroCall::Print(_, format) => {
+                write!(w, "@import(\"std\").io.getStdOut().writer().print(")?;
+                lower_format(format, "", w, ctx)?;
+                write!(w, ") catch @panic(\"Failed to write to stdout\")")
             }
-            dst::Expr::FunctionCall(c) => c.lower(w),
+            dst::MacroCall::Println(_, format) => {
+                write!(w, "@import(\"std\").io.getStdOut().writer().print(")?;
+                lower_format(format, "\n", w, ctx)?;
+                write!(w, ") catch @panic(\"Failed to write to stdout\")")
+            }
+            dst::MacroCall::Format(_, format) => lower_alloc_format(format, w, ctx),
         }
     }
 }
 
-impl Lowerable for dst::Call {
-    fn lower(&self, w: &mut dyn Write) -> io::Result<()> {
-        if let Some(builtin) = &self.callee.as_ref().borrow().builtin {
-            match builtin {
-                dst::function::Builtin::BoolEq => {
-                    self.args[0].lower(w)?;
-                    write!(w, " == ")?;
-                    self.args[1].lower(w)
-                }
-            }
-        } else {
-            unimplemented!("Lowering non-builtin structs")
+impl Lowerable for dst::Report {
+    /// Lower a call to the runtime panic function, which never returns.
+    fn lower(&self, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+        let escape = |text: &str| text.replace('{', "{{").replace('}', "}}");
+        let mut format = escape(&self.text);
 
-            // write!(w, "{}(", self.callee.as_ref().borrow().id())?;
+        if self.message.is_some() {
+            format.push_str(": {s}");
+        }
 
-            // for (i, arg) in self.args.iter().enumerate() {
-            //     if i > 0 {
-            //         write!(w, ", ")?;
-            //     }
+        format.push_str(" at ");
+        format.push_str(&escape(&self.location));
 
-            //     arg.lower(w)?;
-            // }
+        write!(
+            w,
+            "@import(\"{}\").panic({}, .{{",
+            RUNTIME,
+            ZigString(&format)
+        )?;
 
-            // write!(w, ")")
+        if let Some(message) = &self.message {
+            write!(w, " ")?;
+            message.lower(w, ctx)?;
+            write!(w, " ")?;
         }
+
+        write!(w, "}})")
     }
 }
 
-impl Lowerable for dst::VarDecl {
-    fn lower(&self, w: &mut dyn Write) -> io::Result<()> {
-        write!(w, "var @\"{}\" = ", self.id().value)?;
-        self.expr.lower(w)?;
-        Ok(())
+/// Write a formatted string allocation, or a plain string if there are no arguments.
+fn lower_alloc_format(format: &dst::Format, w: &mut dyn Write, ctx: &Context) -> io::Result<()> {
+    if format.args.is_empty() {
+        return write!(w, "@as([]const u8, {})", ZigString(&format.segments[0]));
     }
+
+    write!(
+        w,
+        "@as([]const u8, @import(\"std\").fmt.allocPrint(@import(\"std\").heap.c_allocator, "
+    )?;
+    lower_format(format, "", w, ctx)?;
+    write!(w, ") catch @panic(\"Out of memory\"))")
 }
 
-impl Lowerable for dst::MacroCall {
-    fn lower(&self, w: &mut dyn Write) -> io::Result<()> {
-        match self {
-            dst::MacroCall::Assert(_, expr) => {
-                write!(w, "@import(\"std\").debug.assert(")?;
-                expr.lower(w)?;
-                write!(w, ")")?;
-                Ok(())
-            }
+/// Write Zig `std.fmt` arguments, i.e. a format string and an arguments tuple.
+/// Placeholders are chosen by argument types; struct values are formatted
+/// as their names, e.g. `Foo { }`, for they have no fields yet.
+fn lower_format(
+    format: &dst::Format,
+    suffix: &str,
+    w: &mut dyn Write,
+    ctx: &Context,
+) -> io::Result<()> {
+    let mut zig_format = String::new();
+
+    for (i, segment) in format.segments.iter().enumerate() {
+        if i > 0 {
+            let decl = format.args[i - 1].1.as_struct().unwrap().borrow();
+
+            zig_format.push_str(match decl.builtin {
+                Some(dst::r#struct::Builtin::Bool) => "{}",
+                Some(dst::r#struct::Builtin::CInt) => "{d}",
+                Some(dst::r#struct::Builtin::String | dst::r#struct::Builtin::CString) | None => {
+                    "{s}"
+                }
+            });
+        }
+
+        zig_format.push_str(&segment.replace('{', "{{").replace('}', "}}"));
+    }
+
+    zig_format.push_str(suffix);
+    write!(w, "{}, .{{", ZigString(&zig_format))?;
+
+    for (i, (arg, r#type)) in format.args.iter().enumerate() {
+        write!(w, "{}", if i > 0 { ", " } else { " " })?;
+        let decl = r#type.as_struct().unwrap().borrow();
+
+        if decl.builtin.is_some() {
+            arg.lower(w, ctx)?;
+        } else {
+            // Evaluate the argument, but format the struct name.
+            let name = decl
+                .namespace
+                .iter()
+                .chain(std::iter::once(&decl.id()))
+                .map(|id| id.value.as_str())
+                .collect::<Vec<_>>()
+                .join("::");
+            let name = format!("{} {{ }}", name);
+            write!(
+                w,
+                "(struct {{ fn f(_: anytype) []const u8 {{ return {}; }} }}).f(",
+                ZigString(&name)
+            )?;
+            arg.lower(w, ctx)?;
+            write!(w, ")")?;
         }
     }
+
+    if !format.args.is_empty() {
+        write!(w, " ")?;
+    }
+
+    write!(w, "}}")
 }
 
 #[cfg(test)]
@@ -164,12 +1073,13 @@ mod test {
     fn assert_lowering(input: &str, expected: &str) {
         let ast_module = crate::parser::parse_simple(input);
         let program = Program::new(".cache".into());
-        let unit = Unit::new(Rc::downgrade(&program), "<test>".into());
+        let unit = Unit::with_source(Rc::downgrade(&program), "<test>".into(), input.to_string());
         let dst_module = ast_module
             .resolve(Rc::downgrade(&unit))
             .expect("Failed to resolve");
         let mut buf = Vec::<u8>::new();
-        dst_module.lower(&mut buf).expect("Failed to lower");
+        let ctx = Context::new(Rc::downgrade(&unit));
+        dst_module.lower(&mut buf, &ctx).expect("Failed to lower");
         assert_eq!(String::from_utf8(buf).unwrap(), expected);
     }
 
@@ -180,9 +1090,10 @@ mod test {
 let a = true
 @assert(a)
             "#,
-            r#"pub fn main() void {
-var @"a" = true;
-@import("std").debug.assert(@"a");
+            r#"pub var @"`let a": bool = undefined;
+pub fn @"`init"() void {
+@"`let a" = true;
+if (!(@"`let a")) @import("runtime.zig").panic("Assertion failed: `a` at <test>:3:1..3:11", .{});
 }
 "#,
         );
@@ -195,10 +1106,208 @@ let a = false
 a = true;
 @assert(a)
             "#,
+            r#"pub var @"`let a": bool = undefined;
+pub fn @"`init"() void {
+@"`let a" = false;
+@"`let a" = true;
+if (!(@"`let a")) @import("runtime.zig").panic("Assertion failed: `a` at <test>:4:1..4:11", .{});
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_wrapped_id() {
+        assert_lowering(
+            r#"
+let `hello "world"` = false
+@assert(`hello "world"`)
+            "#,
+            r#"pub var @"`let hello \"world\"": bool = undefined;
+pub fn @"`init"() void {
+@"`let hello \"world\"" = false;
+if (!(@"`let hello \"world\"")) @import("runtime.zig").panic("Assertion failed: ``hello \"world\"`` at <test>:3:1..3:25", .{});
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_closure() {
+        assert_lowering(
+            r#"
+let x = true
+let f = fn [&x]() { x = false; }
+f()
+            "#,
+            r#"pub var @"`let f": @import("std").meta.Tuple(&.{ ?*const anyopaque, fn (?*const anyopaque) void }) = undefined;
+pub var @"`let x": bool = undefined;
+pub fn @"`init"() void {
+@"`let x" = true;
+@"`let f" = closure22: {
+const @"`Env22" = struct {
+@"x": *bool,
+fn @"`call"(@"`ctx22": ?*const anyopaque) void {
+const @"`env22": *const @This() = @ptrCast(*const @This(), @alignCast(@alignOf(@This()), @"`ctx22".?));
+@"`env22".@"x".* = false;
+}
+};
+const @"`env22" = @import("runtime.zig").closures.allocator().create(@"`Env22") catch @panic("Out of memory");
+@"`env22".* = .{ .@"x" = &@"`let x", };
+break :closure22 @as(@import("std").meta.Tuple(&.{ ?*const anyopaque, fn (?*const anyopaque) void }), .{ @"`env22", @"`Env22".@"`call" });
+};
+@"`let f"[1](@"`let f"[0]);
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_entry_point() {
+        let mut lowered = Vec::new();
+        write_entry_point(&mut lowered, &["a".to_string(), "b".to_string()]).unwrap();
+
+        // Closure environments are freed along with the runtime.
+        assert_eq!(
+            String::from_utf8(lowered).unwrap(),
             r#"pub fn main() void {
-var @"a" = false;
-@"a" = true;
-@import("std").debug.assert(@"a");
+@import("a.zig").@"`init"();
+@import("b.zig").@"`init"();
+@"`init"();
+@import("runtime.zig").deinit();
+}
+"#
+        );
+    }
+
+    #[test]
+    pub fn test_extern() {
+        assert_lowering(
+            r#"
+extern fn rand() -> CInt
+extern fn strerror(errnum: CInt) -> CString
+extern fn printf(format: CString, ...) -> CInt
+let s = strerror(rand())
+let x = printf(s, rand(), s, true)
+            "#,
+            r#"pub extern "c" fn @"printf"(@"format": [*c]const u8, ...) c_int;
+pub extern "c" fn @"rand"() c_int;
+pub var @"`let s": [*c]const u8 = undefined;
+pub extern "c" fn @"strerror"(@"errnum": c_int) [*c]const u8;
+pub var @"`let x": c_int = undefined;
+pub fn @"`init"() void {
+@"`let s" = @"strerror"(@"rand"());
+@"`let x" = @"printf"(@"`let s", @import("runtime.zig").promote(@"rand"()), @import("runtime.zig").promote(@"`let s"), @import("runtime.zig").promote(true));
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_print() {
+        assert_lowering(
+            r#"
+@[Extern]
+struct Foo { }
+extern fn foo() -> Foo
+let s = @format("{} {{}}", true)
+@print("{}", s)
+@println("{}, {}", s, foo())
+            "#,
+            r#"pub const @"Foo" = extern struct {};
+pub extern "c" fn @"foo"() @"Foo";
+pub var @"`let s": []const u8 = undefined;
+pub fn @"`init"() void {
+@"`let s" = @as([]const u8, @import("std").fmt.allocPrint(@import("std").heap.c_allocator, "{} {{}}", .{ true }) catch @panic("Out of memory"));
+@import("std").io.getStdOut().writer().print("{s}", .{ @"`let s" }) catch @panic("Failed to write to stdout");
+@import("std").io.getStdOut().writer().print("{s}, {s}\n", .{ @"`let s", (struct { fn f(_: anytype) []const u8 { return "Foo { }"; } }).f(@"foo"()) }) catch @panic("Failed to write to stdout");
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_interpolation() {
+        assert_lowering(
+            r#"
+let x = true
+let s = "x is {x}, {{s}} is {"{x}"}"
+let t = "{{}}"
+            "#,
+            r#"pub var @"`let s": []const u8 = undefined;
+pub var @"`let t": []const u8 = undefined;
+pub var @"`let x": bool = undefined;
+pub fn @"`init"() void {
+@"`let x" = true;
+@"`let s" = @as([]const u8, @import("std").fmt.allocPrint(@import("std").heap.c_allocator, "x is {}, {{s}} is {s}", .{ @"`let x", @as([]const u8, @import("std").fmt.allocPrint(@import("std").heap.c_allocator, "{}", .{ @"`let x" }) catch @panic("Out of memory")) }) catch @panic("Out of memory"));
+@"`let t" = @as([]const u8, "{}");
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_runtime_panic() {
+        assert_lowering(
+            r#"
+fn check(x: Bool) -> Bool {
+  @assert(x, "x is {x}")
+  @unreachable()
+}
+fn later() { @todo("{{later}}") }
+@panic("boom")
+            "#,
+            r#"pub fn @"check"(@"x": bool) bool {
+if (!(@"x")) @import("runtime.zig").panic("Assertion failed: `x`: {s} at <test>:3:3..3:25", .{ @as([]const u8, @import("std").fmt.allocPrint(@import("std").heap.c_allocator, "x is {}", .{ @"x" }) catch @panic("Out of memory")) });
+@import("runtime.zig").panic("Reached unreachable code at <test>:4:3..4:17", .{});
+}
+pub fn @"later"() void {
+@import("runtime.zig").panic("Not implemented yet: {s} at <test>:6:14..6:32", .{ @as([]const u8, "{later}") });
+}
+pub fn @"`init"() void {
+@import("runtime.zig").panic("Panicked: {s} at <test>:7:1..7:15", .{ @as([]const u8, "boom") });
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_c_import() {
+        assert_lowering(
+            r#"
+@cimport("stdlib.h")
+extern fn abs(x: CInt) -> CInt
+extern fn rand() -> CInt
+let x = abs(rand())
+            "#,
+            r#"const @"`c" = @cImport({
+@cInclude("stdlib.h");
+});
+pub const @"abs": fn (c_int) callconv(.C) c_int = @"`c".@"abs";
+pub const @"rand": fn () callconv(.C) c_int = @"`c".@"rand";
+pub var @"`let x": c_int = undefined;
+pub fn @"`init"() void {
+@"`let x" = @"abs"(@"rand"());
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_export() {
+        assert_lowering(
+            r#"
+@[Extern]
+struct Handle { }
+
+@[Export("nx_id")]
+fn id(handle: Handle) -> Handle { return handle }
+            "#,
+            r#"pub const @"Handle" = extern struct {};
+pub export fn @"nx_id"(@"handle": @"Handle") @"Handle" {
+return @"handle";
+}
+pub fn @"`init"() void {
 }
 "#,
         );
@@ -212,10 +1321,318 @@ var @"a" = false;
 let a = false
 let b = true
 eq?(a, b);"#,
-            r#"pub fn main() void {
-var @"a" = false;
-var @"b" = true;
-@"a" == @"b";
+            r#"pub var @"`let a": bool = undefined;
+pub var @"`let b": bool = undefined;
+pub fn @"`init"() void {
+@"`let a" = false;
+@"`let b" = true;
+@"`let a" == @"`let b";
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_instance_call() {
+        assert_lowering(
+            r#"
+let a = false
+let b = true
+@assert(a.eq?(b))"#,
+            r#"pub var @"`let a": bool = undefined;
+pub var @"`let b": bool = undefined;
+pub fn @"`init"() void {
+@"`let a" = false;
+@"`let b" = true;
+if (!(@"`let a" == @"`let b")) @import("runtime.zig").panic("Assertion failed: `a.eq?(b)` at <test>:4:1..4:18", .{});
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_function() {
+        assert_lowering(
+            r#"
+fn id(x: Bool) -> Bool {
+  return x
+}
+
+fn ignore(x: Bool) { }
+
+let a = id(true)
+ignore(a)"#,
+            r#"pub var @"`let a": bool = undefined;
+pub fn @"id"(@"x": bool) bool {
+return @"x";
+}
+pub fn @"ignore"(@"x": bool) void {
+_ = @"x";
+}
+pub fn @"`init"() void {
+@"`let a" = @"id"(true);
+@"ignore"(@"`let a");
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_impl() {
+        assert_lowering(
+            r#"
+struct Foo { }
+
+impl Bool {
+  fn .self?() -> Bool {
+    return self
+  }
+
+  fn ::foo(foo: Foo) { }
+}
+
+let a = true
+@assert(a.self?())"#,
+            r#"pub const @"Foo" = struct {};
+pub var @"`let a": bool = undefined;
+pub fn @"Bool`::self?"(@"self": bool) bool {
+return @"self";
+}
+pub fn @"Bool`::foo"(@"foo": @"Foo") void {
+_ = @"foo";
+}
+pub fn @"`init"() void {
+@"`let a" = true;
+if (!(@"Bool`::self?"(@"`let a"))) @import("runtime.zig").panic("Assertion failed: `a.self?()` at <test>:13:1..13:19", .{});
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_errors() {
+        assert_lowering(
+            r#"
+error NotFound
+
+fn find(x: Bool) -> Bool throws NotFound {
+  @assert(x)
+  throw NotFound
+}
+
+fn retry() -> Bool throws NotFound {
+  return try find(true)
+}
+
+let a = retry() catch false"#,
+            r#"pub var @"`let a": bool = undefined;
+pub fn @"find"(@"x": bool) error{@"3d5f0400`::NotFound"}!bool {
+if (!(@"x")) @import("runtime.zig").panic("Assertion failed: `x` at <test>:5:3..5:13", .{});
+return error.@"3d5f0400`::NotFound";
+}
+pub fn @"retry"() error{@"3d5f0400`::NotFound"}!bool {
+return try @"find"(true);
+}
+pub fn @"`init"() void {
+@"`let a" = (@"retry"() catch false);
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_optionals() {
+        assert_lowering(
+            r#"
+fn unwrap(x: Bool?) -> Bool {
+  if x == nil {
+    return false
+  }
+
+  return x
+}
+
+fn maybe() -> Bool? {
+  return true
+}
+
+if let b = maybe() {
+  @assert(unwrap(b))
+}"#,
+            r#"pub fn @"maybe"() ?bool {
+return true;
+}
+pub fn @"unwrap"(@"x": ?bool) bool {
+if ((@"x" == null)) {
+return false;
+}
+return @"x".?;
+}
+pub fn @"`init"() void {
+if (@"maybe"()) |@"`b"| {
+var @"b" = @"`b";
+if (!(@"unwrap"(@"b"))) @import("runtime.zig").panic("Assertion failed: `unwrap(b)` at <test>:15:3..15:21", .{});
+}
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_namespace() {
+        assert_lowering(
+            r#"
+namespace Net {
+  pub struct Response { }
+
+  impl Response {
+    fn ok?(r: Self) -> Bool {
+      return true
+    }
+  }
+
+  pub fn get() -> Bool {
+    return true
+  }
+}
+
+let a = Net::get()"#,
+            r#"pub const @"Net`::Response" = struct {};
+pub fn @"Net`::get"() bool {
+return true;
+}
+pub var @"`let a": bool = undefined;
+pub fn @"Net`::Response`::ok?"(@"r": @"Net`::Response") bool {
+_ = @"r";
+return true;
+}
+pub fn @"`init"() void {
+@"`let a" = @"Net`::get"();
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_namespace_wrapped_id() {
+        // A wrapped id never collides with a namespaced declaration.
+        assert_lowering(
+            r#"
+namespace Net {
+  pub fn get() -> Bool {
+    return true
+  }
+}
+
+fn `Net::get`() -> Bool {
+  return false
+}"#,
+            r#"pub fn @"Net`::get"() bool {
+return true;
+}
+pub fn @"Net::get"() bool {
+return false;
+}
+pub fn @"`init"() void {
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_types() {
+        assert_lowering(
+            r#"
+fn pick(pair: (Bool, CInt), ptr: *CInt?, f: fn(*Bool) -> Bool) { }"#,
+            r#"pub fn @"pick"(@"pair": @import("std").meta.Tuple(&.{ bool, c_int }), @"ptr": ?*c_int, @"f": @import("std").meta.Tuple(&.{ ?*const anyopaque, fn (?*const anyopaque, *bool) bool })) void {
+_ = @"pair";
+_ = @"ptr";
+_ = @"f";
+}
+pub fn @"`init"() void {
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_generic_struct() {
+        assert_lowering(
+            r#"
+struct Pair<A, B> { }
+fn swap(pair: Pair<Bool, CInt>) -> Pair<CInt, Bool> { @todo() }"#,
+            r#"pub fn @"Pair"(comptime @"A": type, comptime @"B": type) type {
+_ = @"A";
+_ = @"B";
+return struct {};
+}
+pub fn @"swap"(@"pair": @"Pair"(bool, c_int)) @"Pair"(c_int, bool) {
+_ = @"pair";
+@import("runtime.zig").panic("Not implemented yet at <test>:3:55..3:62", .{});
+}
+pub fn @"`init"() void {
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_declaration_order() {
+        assert_lowering(
+            r#"
+let a = first(Flag::new())
+
+fn first(flag: Flag) -> Bool {
+  return second()
+}
+
+fn second() -> Bool {
+  return true
+}
+
+struct Flag {
+  fn new() -> Flag {
+    return @todo()
+  }
+}"#,
+            r#"pub const @"Flag" = struct {};
+pub var @"`let a": bool = undefined;
+pub fn @"first"(@"flag": @"Flag") bool {
+_ = @"flag";
+return @"second"();
+}
+pub fn @"second"() bool {
+return true;
+}
+pub fn @"Flag`::new"() @"Flag" {
+return @import("runtime.zig").panic("Not implemented yet at <test>:14:12..14:19", .{});
+}
+pub fn @"`init"() void {
+@"`let a" = @"first"(@"Flag`::new"());
+}
+"#,
+        );
+    }
+
+    #[test]
+    pub fn test_globals() {
+        assert_lowering(
+            r#"
+fn toggle() {
+  on = on.eq?(false);
+}
+
+pub let on = false
+toggle()
+@assert(on)"#,
+            r#"pub var @"`let on": bool = undefined;
+pub fn @"toggle"() void {
+@"`let on" = @"`let on" == false;
+}
+pub fn @"`init"() void {
+@"`let on" = false;
+@"toggle"();
+if (!(@"`let on")) @import("runtime.zig").panic("Assertion failed: `on` at <test>:8:1..8:12", .{});
 }
 "#,
         );
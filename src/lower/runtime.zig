@@ -0,0 +1,25 @@
+//! Onyx runtime support, imported by lowered units as `runtime.zig`.
+
+const std = @import("std");
+
+/// Owns closure environments, which are shared by copies of a function value.
+/// Freed by `deinit` once the program finishes, see `lower::write_entry_point`.
+/// A library has no entry point, thus its environments live until it's unloaded.
+pub var closures = std.heap.ArenaAllocator.init(std.heap.c_allocator);
+
+/// Free the runtime resources, called at the end of the program entry point.
+pub fn deinit() void {
+    closures.deinit();
+}
+
+/// Promote a C variadic argument as C does, i.e. `bool` to `c_int`,
+/// since Zig passes variadic arguments as is.
+pub fn promote(value: anytype) if (@TypeOf(value) == bool) c_int else @TypeOf(value) {
+    return if (@TypeOf(value) == bool) @boolToInt(value) else value;
+}
+
+/// Report an Onyx runtime panic to stderr and exit with a non-zero status.
+pub fn panic(comptime format: []const u8, args: anytype) noreturn {
+    std.debug.print("\x1b[41m \x1b[1m  PANIC  \x1b[0m " ++ format ++ "\n", args);
+    std.process.exit(1);
+}
@@ -0,0 +1,282 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::{
+    program::{Optimize, OutputKind},
+    Panic,
+};
+
+/// A package manifest, i.e. an `nx.toml` file.
+///
+/// ```toml
+/// registry = "../registry"
+///
+/// [package]
+/// name = "hello"
+/// version = "0.1.0"
+/// entry = "src/main.nx"
+/// sources = ["src"]
+///
+/// [build]
+/// output = "bin/hello"
+/// zig = "/opt/zig/zig"
+/// optimize = "release-fast"
+///
+/// [dependencies]
+/// collections = { path = "../collections" }
+/// strings = { version = "^1.2" }
+/// ```
+///
+/// Paths are relative to the manifest directory.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// The directory containing the manifest file.
+    #[serde(skip)]
+    pub dir: PathBuf,
+
+    /// A local registry directory of `<name>/<version>` package directories.
+    pub registry: Option<PathBuf>,
+
+    pub package: Package,
+
+    #[serde(default)]
+    pub build: Build,
+
+    /// Dependencies by package name.
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Package {
+    pub name: String,
+
+    /// The package version, required to be published in a registry.
+    pub version: Option<Version>,
+
+    /// The entry unit path.
+    #[serde(default = "Package::default_entry")]
+    pub entry: PathBuf,
+
+    /// Source roots, which are searched for bare import specifiers.
+    #[serde(default = "Package::default_sources")]
+    pub sources: Vec<PathBuf>,
+}
+
+impl Package {
+    fn default_entry() -> PathBuf {
+        PathBuf::from("src/main.nx")
+    }
+
+    fn default_sources() -> Vec<PathBuf> {
+        vec![PathBuf::from("src")]
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Build {
+    /// The output path, the package name by default.
+    pub output: Option<PathBuf>,
+
+    /// Build a C library instead of an executable.
+    pub lib: Option<LibKind>,
+
+    /// The Zig executable path.
+    pub zig: Option<PathBuf>,
+
+    /// The cache directory path.
+    pub cache: Option<PathBuf>,
+
+    #[serde(default)]
+    pub optimize: Optimize,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum LibKind {
+    Static,
+    Shared,
+}
+
+impl From<LibKind> for OutputKind {
+    fn from(kind: LibKind) -> Self {
+        match kind {
+            LibKind::Static => OutputKind::StaticLibrary,
+            LibKind::Shared => OutputKind::SharedLibrary,
+        }
+    }
+}
+
+/// A dependency, either a local path, e.g. `{ path = "../collections" }`,
+/// or a registry package version, e.g. `{ version = "^1.2" }`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Dependency {
+    /// The dependency package directory, containing its own manifest.
+    pub path: Option<PathBuf>,
+
+    /// The version constraint, checked against a path dependency version.
+    pub version: Option<VersionReq>,
+}
+
+pub const LOCK_FILE_NAME: &str = "nx.lock";
+
+impl Manifest {
+    pub const FILE_NAME: &'static str = "nx.toml";
+
+    /// Return the manifest path in `dir`, if it exists.
+    pub fn find(dir: &Path) -> Option<PathBuf> {
+        let path = dir.join(Self::FILE_NAME);
+        path.is_file().then_some(path)
+    }
+
+    /// Read and parse the manifest at `path`.
+    pub fn load(path: &Path) -> Result<Self, Panic> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            Panic::new(
+                format!("Failed to read manifest {}: {}", path.display(), e),
+                None,
+            )
+        })?;
+
+        let mut manifest = Self::parse(&source).map_err(|message| {
+            Panic::new(
+                format!("Failed to parse manifest {}: {}", path.display(), message),
+                None,
+            )
+        })?;
+
+        manifest.dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        Ok(manifest)
+    }
+
+    fn parse(source: &str) -> Result<Self, String> {
+        let manifest: Self = toml::from_str(source).map_err(|e| e.message().to_string())?;
+
+        for (name, dependency) in &manifest.dependencies {
+            if dependency.path.is_none() && dependency.version.is_none() {
+                return Err(format!(
+                    "dependency `{}` must have either `path` or `version`",
+                    name
+                ));
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn entry_path(&self) -> PathBuf {
+        self.dir.join(&self.package.entry)
+    }
+
+    pub fn output_path(&self) -> PathBuf {
+        match &self.build.output {
+            Some(output) => self.dir.join(output),
+            None => self.dir.join(&self.package.name),
+        }
+    }
+
+    /// Return the lockfile path, next to the manifest.
+    pub fn lock_path(&self) -> PathBuf {
+        self.dir.join(LOCK_FILE_NAME)
+    }
+
+    pub fn cache_path(&self) -> PathBuf {
+        match &self.build.cache {
+            Some(cache) => self.dir.join(cache),
+            None => self.dir.join(".cache"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let manifest = Manifest::parse("[package]\nname = \"hello\"\n").unwrap();
+
+        assert_eq!(manifest.package.name, "hello");
+        assert_eq!(manifest.entry_path(), PathBuf::from("src/main.nx"));
+        assert_eq!(manifest.output_path(), PathBuf::from("hello"));
+        assert_eq!(manifest.cache_path(), PathBuf::from(".cache"));
+        assert_eq!(manifest.build.optimize, Optimize::Debug);
+        assert!(manifest.dependencies.is_empty());
+    }
+
+    #[test]
+    fn test_full() {
+        let manifest = Manifest::parse(
+            r#"
+registry = "../registry"
+
+[package]
+name = "hello"
+version = "0.1.0"
+entry = "main.nx"
+sources = [".", "lib"]
+
+[build]
+output = "bin/hello"
+lib = "shared"
+zig = "/opt/zig/zig"
+optimize = "release-fast"
+
+[dependencies]
+collections = { path = "../collections" }
+strings = { version = "^1.2" }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.registry, Some(PathBuf::from("../registry")));
+        assert_eq!(manifest.package.version, Some(Version::new(0, 1, 0)));
+        assert_eq!(manifest.package.sources.len(), 2);
+        assert_eq!(manifest.output_path(), PathBuf::from("bin/hello"));
+        assert_eq!(manifest.build.lib, Some(LibKind::Shared));
+        assert_eq!(manifest.build.zig, Some(PathBuf::from("/opt/zig/zig")));
+        assert_eq!(manifest.build.optimize, Optimize::ReleaseFast);
+        assert_eq!(
+            manifest.dependencies["collections"].path,
+            Some(PathBuf::from("../collections"))
+        );
+        assert_eq!(
+            manifest.dependencies["strings"].version,
+            Some(VersionReq::parse("^1.2").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(Manifest::parse("[package]\n").is_err());
+        assert!(Manifest::parse("[package]\nname = \"a\"\nfoo = 1\n").is_err());
+        assert!(Manifest::parse("[package]\nname = \"a\"\n[dependencies]\nb = {}\n").is_err());
+        assert!(
+            Manifest::parse("[package]\nname = \"a\"\n[build]\noptimize = \"fast\"\n").is_err()
+        );
+    }
+
+    #[test]
+    fn test_load() {
+        let manifest = Manifest::load(Path::new("lang/spec/package/nx.toml")).unwrap();
+
+        assert_eq!(
+            manifest.entry_path(),
+            PathBuf::from("lang/spec/package/main.nx")
+        );
+
+        assert_eq!(
+            manifest.lock_path(),
+            PathBuf::from("lang/spec/package/nx.lock")
+        );
+    }
+}
@@ -0,0 +1,595 @@
+use std::{
+    path::{Component, Path, PathBuf},
+    rc::Rc,
+};
+
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    manifest::{Dependency, Manifest},
+    Panic,
+};
+
+/// A resolved package, either the root one or a dependency.
+#[derive(Debug)]
+pub struct Package {
+    pub name: String,
+    pub version: Option<Version>,
+    pub source: Source,
+
+    /// The canonical package directory.
+    pub dir: PathBuf,
+
+    /// Source roots, searched for the package bare import specifiers.
+    pub sources: Vec<PathBuf>,
+
+    /// Names of direct dependencies, which prefix bare import specifiers,
+    /// e.g. `"collections/list"`.
+    pub dependencies: Vec<String>,
+
+    /// The package content hash, `None` for the root package.
+    pub hash: Option<String>,
+}
+
+impl Package {
+    /// Return the name of a module at canonical `path` within the package,
+    /// relative to its source root sans extension, e.g. `collections/list`.
+    pub fn module_name(&self, path: &Path) -> String {
+        let relative = self
+            .sources
+            .iter()
+            .chain(std::iter::once(&self.dir))
+            .find_map(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+
+        Path::new(&self.name)
+            .join(relative.with_extension(""))
+            .display()
+            .to_string()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Source {
+    Root,
+    Path,
+    Registry,
+}
+
+/// An `nx.lock` file, recording the exact dependency versions and contents.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct LockedPackage {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+
+    pub source: Source,
+
+    /// The package directory, relative to the root package.
+    pub path: PathBuf,
+
+    /// The content hash, e.g. `blake3:af13…`.
+    pub hash: String,
+
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+impl Lockfile {
+    const HEADER: &'static str = "# This file is generated by nx, do not edit it by hand.\n\n";
+
+    /// Read the lockfile at `path`, or return an empty one if it doesn't exist.
+    pub fn load(path: &Path) -> Result<Self, Panic> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            Panic::new(
+                format!("Failed to read lockfile {}: {}", path.display(), e),
+                None,
+            )
+        })?;
+
+        toml::from_str(&source).map_err(|e| {
+            Panic::new(
+                format!(
+                    "Failed to parse lockfile {}: {}",
+                    path.display(),
+                    e.message()
+                ),
+                None,
+            )
+        })
+    }
+
+    /// Write the lockfile at `path` unless it's already up to date.
+    pub fn write(&self, path: &Path) -> Result<(), Panic> {
+        let contents = Self::HEADER.to_string() + &toml::to_string(self).unwrap();
+
+        if std::fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+            return Ok(());
+        }
+
+        std::fs::write(path, contents).map_err(|e| {
+            Panic::new(
+                format!("Failed to write lockfile {}: {}", path.display(), e),
+                None,
+            )
+        })
+    }
+
+    fn find(&self, name: &str) -> Option<&LockedPackage> {
+        self.packages.iter().find(|p| p.name == name)
+    }
+}
+
+/// Resolve the root package and its dependencies, transitively.
+/// The root package comes first, then the dependencies in resolution order.
+///
+/// Registry dependencies prefer the locked version if it still matches
+/// the constraint, and their contents must match the locked hash.
+/// The lockfile next to the root manifest is read, but never written,
+/// see [`lockfile`].
+/// Only the local file system is accessed.
+pub fn resolve(manifest: &Manifest) -> Result<Vec<Rc<Package>>, Panic> {
+    let mut resolver = Resolver {
+        registry: manifest.registry.as_ref().map(|r| manifest.dir.join(r)),
+        locked: Lockfile::load(&manifest.lock_path())?,
+        packages: Vec::new(),
+    };
+
+    resolver.add(manifest, canonical_dir(&manifest.dir)?, Source::Root, None)?;
+    Ok(resolver.packages.into_iter().map(Rc::new).collect())
+}
+
+/// Return the lockfile recording resolved `packages`, the root one first.
+pub fn lockfile(packages: &[Rc<Package>]) -> Lockfile {
+    let root_dir = &packages[0].dir;
+
+    let mut lockfile = Lockfile {
+        packages: packages[1..]
+            .iter()
+            .map(|package| LockedPackage {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                source: package.source,
+                path: relative_path(root_dir, &package.dir),
+                hash: package.hash.clone().unwrap(),
+                dependencies: package.dependencies.clone(),
+            })
+            .collect(),
+    };
+
+    lockfile.packages.sort_by(|a, b| a.name.cmp(&b.name));
+    lockfile
+}
+
+struct Resolver {
+    registry: Option<PathBuf>,
+    locked: Lockfile,
+    packages: Vec<Package>,
+}
+
+impl Resolver {
+    fn add(
+        &mut self,
+        manifest: &Manifest,
+        dir: PathBuf,
+        source: Source,
+        hash: Option<String>,
+    ) -> Result<(), Panic> {
+        self.packages.push(Package {
+            name: manifest.package.name.clone(),
+            version: manifest.package.version.clone(),
+            source,
+            sources: manifest
+                .package
+                .sources
+                .iter()
+                .map(|s| dir.join(s))
+                .collect(),
+            dir,
+            dependencies: manifest.dependencies.keys().cloned().collect(),
+            hash,
+        });
+
+        for (name, dependency) in &manifest.dependencies {
+            self.add_dependency(manifest, name, dependency)?;
+        }
+
+        Ok(())
+    }
+
+    fn add_dependency(
+        &mut self,
+        requirer: &Manifest,
+        name: &str,
+        dependency: &Dependency,
+    ) -> Result<(), Panic> {
+        if let Some(existing) = self.packages.iter().find(|p| p.name == name) {
+            return self.check_existing(existing, requirer, name, dependency);
+        }
+
+        let (dir, source) = match &dependency.path {
+            Some(path) => (canonical_dir(&requirer.dir.join(path))?, Source::Path),
+            None => (
+                self.select_version(requirer, name, dependency)?,
+                Source::Registry,
+            ),
+        };
+
+        let manifest = match Manifest::find(&dir) {
+            Some(path) => Manifest::load(&path)?,
+            None => {
+                let mut panic = Panic::new(
+                    format!("Dependency `{}` has no {}", name, Manifest::FILE_NAME),
+                    None,
+                );
+
+                panic.add_note(format!("Looked in {}", dir.display()), None);
+                panic.add_note(required_by(requirer), None);
+
+                return Err(panic);
+            }
+        };
+
+        if manifest.package.name != name {
+            let mut panic = Panic::new(
+                format!(
+                    "Dependency `{}` is package `{}`",
+                    name, manifest.package.name
+                ),
+                None,
+            );
+
+            panic.add_note(format!("Declared in {}", dir.display()), None);
+            panic.add_note(required_by(requirer), None);
+
+            return Err(panic);
+        }
+
+        if let Some(req) = &dependency.version {
+            if !manifest
+                .package
+                .version
+                .as_ref()
+                .is_some_and(|v| req.matches(v))
+            {
+                return Err(version_mismatch(
+                    requirer,
+                    name,
+                    req,
+                    manifest.package.version.as_ref(),
+                ));
+            }
+        }
+
+        let hash = hash_dir(&dir)?;
+
+        if source == Source::Registry {
+            if let Some(locked) = self.locked.find(name) {
+                if locked.source == Source::Registry
+                    && locked.version == manifest.package.version
+                    && locked.hash != hash
+                {
+                    let mut panic = Panic::new(
+                        format!(
+                            "Package `{}` {} doesn't match its locked content hash",
+                            name,
+                            locked.version.as_ref().unwrap()
+                        ),
+                        None,
+                    );
+
+                    panic.add_note(format!("Locked {}", locked.hash), None);
+                    panic.add_note(format!("Found {} in {}", hash, dir.display()), None);
+                    panic.add_note(
+                        "Published registry packages must not change".to_string(),
+                        None,
+                    );
+
+                    return Err(panic);
+                }
+            }
+        }
+
+        self.add(&manifest, dir, source, Some(hash))
+    }
+
+    /// Check a dependency on an already resolved package,
+    /// as only a single version of a package may be in the program.
+    fn check_existing(
+        &self,
+        existing: &Package,
+        requirer: &Manifest,
+        name: &str,
+        dependency: &Dependency,
+    ) -> Result<(), Panic> {
+        if let Some(path) = &dependency.path {
+            if canonical_dir(&requirer.dir.join(path))? != existing.dir {
+                let mut panic =
+                    Panic::new(format!("Conflicting sources of package `{}`", name), None);
+
+                panic.add_note(format!("Resolved at {}", existing.dir.display()), None);
+                panic.add_note(
+                    format!("{} requires {}", required_by(requirer), path.display()),
+                    None,
+                );
+
+                return Err(panic);
+            }
+        }
+
+        if let Some(req) = &dependency.version {
+            if !existing.version.as_ref().is_some_and(|v| req.matches(v)) {
+                return Err(version_mismatch(
+                    requirer,
+                    name,
+                    req,
+                    existing.version.as_ref(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the directory of the registry package version to use.
+    fn select_version(
+        &self,
+        requirer: &Manifest,
+        name: &str,
+        dependency: &Dependency,
+    ) -> Result<PathBuf, Panic> {
+        let req = dependency.version.as_ref().unwrap();
+
+        let Some(registry) = &self.registry else {
+            let mut panic = Panic::new(
+                format!("Dependency `{}` {} requires a registry", name, req),
+                None,
+            );
+
+            panic.add_note(required_by(requirer), None);
+            panic.add_note(
+                format!(
+                    "Set `registry` in the root {}, or add a `path`",
+                    Manifest::FILE_NAME
+                ),
+                None,
+            );
+
+            return Err(panic);
+        };
+
+        let package_dir = registry.join(name);
+        let mut versions: Vec<Version> = std::fs::read_dir(&package_dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter_map(|e| Version::parse(&e.file_name().to_string_lossy()).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        versions.sort();
+
+        let locked = self
+            .locked
+            .find(name)
+            .filter(|l| l.source == Source::Registry)
+            .and_then(|l| l.version.as_ref());
+
+        let selected = match locked {
+            Some(locked) if req.matches(locked) && versions.contains(locked) => Some(locked),
+            _ => versions.iter().rev().find(|v| req.matches(v)),
+        };
+
+        match selected {
+            Some(version) => canonical_dir(&package_dir.join(version.to_string())),
+            None => {
+                let mut panic = Panic::new(
+                    format!("No version of package `{}` matches {}", name, req),
+                    None,
+                );
+
+                panic.add_note(required_by(requirer), None);
+
+                if versions.is_empty() {
+                    panic.add_note(format!("Looked in {}", package_dir.display()), None);
+                } else {
+                    panic.add_note(
+                        format!(
+                            "Available versions are {}",
+                            versions
+                                .iter()
+                                .map(|v| v.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                        None,
+                    );
+                }
+
+                Err(panic)
+            }
+        }
+    }
+}
+
+fn required_by(requirer: &Manifest) -> String {
+    format!("Required by package `{}`", requirer.package.name)
+}
+
+fn version_mismatch(
+    requirer: &Manifest,
+    name: &str,
+    req: &VersionReq,
+    version: Option<&Version>,
+) -> Panic {
+    let mut panic = Panic::new(
+        format!(
+            "Package `{}` {} doesn't match {}",
+            name,
+            version.map_or("without version".to_string(), |v| v.to_string()),
+            req
+        ),
+        None,
+    );
+
+    panic.add_note(required_by(requirer), None);
+    panic
+}
+
+fn canonical_dir(dir: &Path) -> Result<PathBuf, Panic> {
+    // An empty path is the current directory.
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+
+    dir.canonicalize()
+        .ok()
+        .filter(|d| d.is_dir())
+        .ok_or_else(|| {
+            Panic::new(
+                format!("Package directory {} doesn't exist", dir.display()),
+                None,
+            )
+        })
+}
+
+/// Return the hash of the package directory contents,
+/// ignoring hidden entries and the lockfile.
+pub fn hash_dir(dir: &Path) -> Result<String, Panic> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort();
+
+    let mut hasher = blake3::Hasher::new();
+
+    for file in files {
+        let contents = std::fs::read(dir.join(&file)).map_err(|e| {
+            Panic::new(
+                format!("Failed to read {}: {}", dir.join(&file).display(), e),
+                None,
+            )
+        })?;
+
+        // Separate paths from contents so that moved bytes change the hash.
+        hasher.update(file.to_string_lossy().as_bytes());
+        hasher.update(&[0]);
+        hasher.update(&(contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+
+    Ok(format!("blake3:{}", hasher.finalize().to_hex()))
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Panic> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| Panic::new(format!("Failed to read {}: {}", dir.display(), e), None))?;
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name();
+
+        if name.to_string_lossy().starts_with('.') || name == crate::manifest::LOCK_FILE_NAME {
+            continue;
+        }
+
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else {
+            files.push(path.strip_prefix(root).unwrap().to_path_buf());
+        }
+    }
+
+    Ok(())
+}
+
+/// Return `to` relative to `from`, both canonical.
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut path = PathBuf::new();
+
+    for _ in common..from.len() {
+        path.push("..");
+    }
+
+    for component in &to[common..] {
+        path.push(component);
+    }
+
+    path
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(Path::new("/a/b/c"), Path::new("/a/d/e")),
+            PathBuf::from("../../d/e")
+        );
+
+        assert_eq!(
+            relative_path(Path::new("/a/b"), Path::new("/a/b/c")),
+            PathBuf::from("c")
+        );
+    }
+
+    #[test]
+    fn test_hash_dir() {
+        let hash = hash_dir(Path::new("lang/spec/registry/strings/1.2.0")).unwrap();
+
+        assert!(hash.starts_with("blake3:"));
+        assert_eq!(
+            hash,
+            hash_dir(Path::new("lang/spec/registry/strings/1.2.0")).unwrap()
+        );
+        assert_ne!(
+            hash,
+            hash_dir(Path::new("lang/spec/registry/strings/1.0.0")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_module_name() {
+        let package = Package {
+            name: "collections".to_string(),
+            version: None,
+            source: Source::Path,
+            dir: PathBuf::from("/pkg"),
+            sources: vec![PathBuf::from("/pkg/src")],
+            dependencies: Vec::new(),
+            hash: None,
+        };
+
+        assert_eq!(
+            package.module_name(Path::new("/pkg/src/list.nx")),
+            "collections/list"
+        );
+
+        assert_eq!(
+            package.module_name(Path::new("/pkg/test/list.nx")),
+            "collections/test/list"
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::{ast, location::Cursor, location::Span, unit::Unit, Location, Panic};
+use crate::{
+    ast,
+    location::{Cursor, HasSpan, Span},
+    unit::Unit,
+    Location, Panic,
+};
 
 macro_rules! span {
     ($begin: expr, $end: expr) => {
@@ -38,23 +43,56 @@ peg::parser! {
     // Atoms ==================================================================
     //
 
-    /// An Onyx idetifier.
+    /// A reserved word, which can only be used as an identifier if wrapped.
+    rule keyword()
+        = (
+            "let" / "fn" / "pub" / "extern" / "import" / "from" / "struct" /
+            "impl" / "namespace" / "return" / "true" / "false" / "error" /
+            "throws" / "throw" / "try" / "catch" / "nil" / "if" / "else" / "as"
+        ) !['_' | 'a'..='z' | 'A'..='Z' | '0'..='9' | '?']
+
+    /// An Onyx idetifier, either raw or wrapped in backticks.
     rule id() -> ast::Id
         =
             begin:position!()
             value:$(quiet!{
+                !keyword()
                 ("_" / ['a'..='z' | 'A'..='Z'])
                 ("_" / ['a'..='z' | 'A'..='Z' | '0'..='9'])*
                 "?"?
             } / expected!("identifier"))
             end:position!()
         { ast::Id::new(span!(begin, end), value.to_string()) }
+        /
+            begin:position!()
+            "`" value:$([^ '`' | '\n' | '\r']+) "`"
+            end:position!()
+        { ast::Id::new_wrapped(span!(begin, end), value.to_string()) }
+
+    /// A type identifier, which unlike `id()` never ends with `?`,
+    /// thus `Bool?` is an optional `Bool`.
+    rule type_id() -> ast::Id
+        =
+            begin:position!()
+            value:$(quiet!{
+                !keyword()
+                ("_" / ['a'..='z' | 'A'..='Z'])
+                ("_" / ['a'..='z' | 'A'..='Z' | '0'..='9'])*
+            } / expected!("identifier"))
+            end:position!()
+        { ast::Id::new(span!(begin, end), value.to_string()) }
+        / &"`" it:id() { it }
 
     /// A boolean literal.
     rule bool() -> ast::literal::Bool
         = begin:position!() value:$("true" / "false") end:position!()
         { ast::literal::Bool::new(span!(begin, end), value == "true") }
 
+    /// A `nil` literal.
+    rule nil() -> ast::literal::Nil
+        = begin:position!() "nil" end:position!()
+        { ast::literal::Nil::new(span!(begin, end)) }
+
     /// A string literal.
     rule string() -> ast::literal::String
         = begin:position!() "\"" string:$((!"\"" [_])*) "\"" end:position!()
@@ -63,23 +101,196 @@ peg::parser! {
     // Expressions ============================================================
     //
 
+    /// A string expression, either a plain literal or an interpolation,
+    /// e.g. `"value is {x}"`. Braces are escaped as `{{` and `}}`.
+    rule string_expr() -> ast::Expr
+        = begin:position!() "\"" segments:string_segment()* "\"" end:position!()
+        {
+            let mut merged: Vec<ast::interpolation::Segment> = Vec::new();
+
+            for segment in segments {
+                match (merged.last_mut(), segment) {
+                    (
+                        Some(ast::interpolation::Segment::Text(last)),
+                        ast::interpolation::Segment::Text(text),
+                    ) => last.push_str(&text),
+                    (_, segment) => merged.push(segment),
+                }
+            }
+
+            match merged.as_slice() {
+                [] => ast::Expr::StringLiteral(
+                    ast::literal::String::new(span!(begin, end), String::new()),
+                ),
+                [ast::interpolation::Segment::Text(text)] => ast::Expr::StringLiteral(
+                    ast::literal::String::new(span!(begin, end), text.clone()),
+                ),
+                _ => ast::Expr::Interpolation(
+                    ast::Interpolation::new(span!(begin, end), merged),
+                ),
+            }
+        }
+
+    rule string_segment() -> ast::interpolation::Segment
+        = text:$((!['"' | '{' | '}'] [_])+)
+        { ast::interpolation::Segment::Text(text.to_string()) }
+        / "{{" { ast::interpolation::Segment::Text("{".to_string()) }
+        / "}}" { ast::interpolation::Segment::Text("}".to_string()) }
+        / begin:position!() "{" _? "}" end:position!()
+        { ast::interpolation::Segment::Placeholder(span!(begin, end)) }
+        / "{" _? it:expr() _? "}"
+        { ast::interpolation::Segment::Expr(it) }
+
+    /// A possibly multi-segment path, e.g. `Net::Http::get`.
     rule qualifier() -> ast::Qualifier
         =
             begin:position!()
-            id:id()
+            head:id()
+            tail:("::" it:id() { it })*
+        {
+            let mut qualifier = ast::Qualifier::new(head.span(), None, head);
+
+            for id in tail {
+                let span = span!(begin, id.span().end.offset);
+                qualifier = ast::Qualifier::new(span, Some(qualifier), id);
+            }
+
+            qualifier
+        }
+
+    /// A type path, e.g. `Net::Response`.
+    rule type_qualifier() -> ast::Qualifier
+        =
+            begin:position!()
+            head:type_id()
+            tail:("::" it:type_id() { it })*
+        {
+            let mut qualifier = ast::Qualifier::new(head.span(), None, head);
+
+            for id in tail {
+                let span = span!(begin, id.span().end.offset);
+                qualifier = ast::Qualifier::new(span, Some(qualifier), id);
+            }
+
+            qualifier
+        }
+
+    /// A type expression, e.g. `Bool`, `Bool?`, `*CInt`, `(Bool, CInt)`,
+    /// `Box<Bool>` or `fn(Bool) -> Bool`.
+    rule type_expr() -> ast::TypeExpr
+        =
+            begin:position!()
+            it:type_expr_atom()
+            marks:("?" end:position!() { end })*
+        {
+            marks.into_iter().fold(it, |inner, end| ast::TypeExpr::Optional {
+                span: span!(begin, end),
+                inner: Box::new(inner),
+            })
+        }
+
+    rule type_expr_atom() -> ast::TypeExpr
+        =
+            begin:position!()
+            "fn" _? "(" ___? params:(type_expr() ** ("," ___?)) ___? ")"
+            return_type:(_? "->" __? it:type_expr() { Box::new(it) })?
+            end:position!()
+        {
+            ast::TypeExpr::Function {
+                span: span!(begin, end),
+                params,
+                return_type,
+            }
+        }
+        / begin:position!() "*" inner:type_expr_atom() end:position!()
+        {
+            ast::TypeExpr::Pointer {
+                span: span!(begin, end),
+                inner: Box::new(inner),
+            }
+        }
+        / begin:position!()
+            "(" ___? elements:(type_expr() ++ ("," ___?)) ___? ")"
             end:position!()
-        { ast::Qualifier::new(span!(begin, end), id) }
+        {
+            // A single parenthesized type is merely grouped.
+            if elements.len() == 1 {
+                elements.into_iter().next().unwrap()
+            } else {
+                ast::TypeExpr::Tuple {
+                    span: span!(begin, end),
+                    elements,
+                }
+            }
+        }
+        / begin:position!()
+            generic:type_qualifier() "<" ___? args:(type_expr() ++ ("," ___?)) ___? ">"
+            end:position!()
+        {
+            ast::TypeExpr::Instance {
+                span: span!(begin, end),
+                generic,
+                args,
+            }
+        }
+        / it:type_qualifier() { ast::TypeExpr::Ref(it) }
+
+    /// A closure capture, e.g. `x` or `&x`.
+    rule capture() -> ast::Capture
+        = begin:position!() by_ref:"&"? id:id() end:position!()
+        { ast::Capture::new(span!(begin, end), id, by_ref.is_some()) }
+
+    /// An anonymous function, e.g. `fn [x, &y](a: Bool) -> Bool { }`.
+    rule closure() -> ast::Closure
+        =
+            begin:position!()
+            "fn" _?
+            captures:("[" ___? it:(capture() ** ("," ___?)) ___? "]" _? { it })?
+            "(" ___? params:function_param() ** ("," ___?) ")" _?
+            return_type:("->" __? it:type_expr() _? { it })?
+            "{" body:block_body() ___? "}"
+            end:position!()
+        {
+            ast::Closure::new(
+                span!(begin, end),
+                captures.unwrap_or_default(),
+                params,
+                return_type,
+                body,
+            )
+        }
 
     /// An expression.
     rule expr() -> ast::Expr = precedence! {
         it:call() { ast::Expr::FunctionCall(it) }
         --
-        l:@ _? op:"=" _? r:(@) {
+        l:@ _? op:"=" !"=" _? r:(@) {
             ast::Expr::Binop(ast::Binop::new(l, "=".to_string(), r))
         }
         --
+        l:(@) _? "==" _? r:@ { ast::Expr::Binop(ast::Binop::new(l, "==".to_string(), r)) }
+        l:(@) _? "!=" _? r:@ { ast::Expr::Binop(ast::Binop::new(l, "!=".to_string(), r)) }
+        --
+        l:(@) _ "catch" _ r:@ { ast::Expr::Catch(ast::Catch::new(l, r)) }
+        --
+        begin:position!() "try" _ it:@ {
+            ast::Expr::Try(ast::Try::new(span!(begin, it.span().end.offset), it))
+        }
+        --
+        receiver:(@) "." callee:qualifier() args:args() end:position!() {
+            ast::Expr::FunctionCall(ast::Call::new(
+                span!(receiver.span().start.offset, end),
+                Some(receiver),
+                callee,
+                args,
+            ))
+        }
+        --
         it:macro_call() { ast::Expr::MacroCall(it) }
         it:bool()       { ast::Expr::BoolLiteral(it) }
+        it:nil()        { ast::Expr::NilLiteral(it) }
+        it:string_expr() { it }
+        it:closure()    { ast::Expr::Closure(it) }
         it:qualifier()  { ast::Expr::Ref(it) }
     }
 
@@ -88,13 +299,13 @@ peg::parser! {
 
     rule call() -> ast::Call
         = begin:position!() callee:qualifier() args:args() end:position!()
-        { ast::Call::new(span!(begin, end), callee, args) }
+        { ast::Call::new(span!(begin, end), None, callee, args) }
 
     /// A macro call.
     rule macro_call() -> ast::MacroCall
         =
             begin:position!()
-            "@" id:id() "(" args:(expr() ** ",") ")"
+            "@" id:id() "(" ___? args:(expr() ** ("," ___?)) ___? ")"
             end:position!()
         { ast::MacroCall::new(span!(begin, end), id, args) }
 
@@ -109,20 +320,45 @@ peg::parser! {
             end:position!()
         { ast::Comment::new(span!(begin, end), text.to_string()) }
 
-    /// An `import` statement.
+    /// An `import` statement, e.g. `import { Foo as Bar } from "foo"`,
+    /// `import * as foo from "foo"` or `import * from "foo"`.
     rule import() -> ast::Import
         =
             begin:position!()
             r#pub:("pub" _)?
-            "import" _? "{" ___? ids:(id() ** ("," ___?)) ___? "}" _?
+            "import" _? imported:imported() _?
             "from" _ from:string()
             end:position!()
-        { ast::Import::new(span!(begin, end), r#pub.is_some(), ids, from) }
+        { ast::Import::new(span!(begin, end), r#pub.is_some(), imported, from) }
+
+    rule imported() -> ast::Imported
+        = "{" ___? ids:(import_id() ** ("," ___?)) ___? "}" { ast::Imported::Ids(ids) }
+        / "*" _ "as" _ id:id() { ast::Imported::Module(id) }
+        / "*" { ast::Imported::All }
+
+    rule import_id() -> ast::ImportId
+        =
+            begin:position!()
+            qualifier:qualifier()
+            alias:(_ "as" _ it:id() { it })?
+            end:position!()
+        { ast::ImportId::new(span!(begin, end), qualifier, alias) }
+
+    /// A C header import, e.g. `@cimport("stdio.h")`.
+    rule c_import() -> ast::CImport
+        =
+            begin:position!()
+            "@cimport" _? "(" _? header:string() _? ")"
+            end:position!()
+        { ast::CImport::new(span!(begin, end), header) }
 
-    /// A decorator, e.g. `@[Foo]`.
+    /// A decorator, e.g. `@[Foo]` or `@[Foo("bar")]`.
     rule decorator() -> ast::Decorator
-        = begin:position!() "@[" id:id() "]" end:position!()
-        { ast::Decorator::new(span!(begin, end), id) }
+        =
+            begin:position!()
+            "@[" id:id() args:("(" it:(string() ** ("," _?)) ")" { it })? "]"
+            end:position!()
+        { ast::Decorator::new(span!(begin, end), id, args.unwrap_or_default()) }
 
     /// An expression explicitly terminated w/ `;`.
     rule terminated_expr() -> ast::Statement
@@ -136,62 +372,157 @@ peg::parser! {
     rule var_decl() -> ast::VarDecl
         =
             begin:position!()
+            r#pub:("pub" _)?
             "let" _ id:id() expr:var_decl_value() term()
             end:position!()
-        { ast::VarDecl::new(span!(begin, end), id, expr) }
+        { ast::VarDecl::new(span!(begin, end), r#pub.is_some(), id, expr) }
 
     /// A struct definition.
     rule struct_def() -> ast::r#struct::Def
         =
             begin:position!()
             r#pub:("pub" _)?
-            "struct" _ id:id() _? "{" ___? "}"
+            "struct" _ id:id() type_params:type_params()? _? "{" body:block_body() ___? "}"
             end:position!()
         {
             ast::r#struct::Def::new(
                 span!(begin, end),
                 r#pub.is_some(),
                 id,
+                type_params.unwrap_or_default(),
+                body,
             )
         }
 
+    /// Generic struct type parameters, e.g. `<T, U>`.
+    rule type_params() -> Vec<ast::Id>
+        = "<" ___? it:(id() ++ ("," ___?)) ___? ">" { it }
+
+    /// A struct implementation.
+    rule struct_impl() -> ast::r#struct::Impl
+        =
+            begin:position!()
+            "impl" _ r#type:qualifier() _? "{" body:block_body() ___? "}"
+            end:position!()
+        { ast::r#struct::Impl::new(span!(begin, end), r#type, body) }
+
+    /// A namespace declaration.
+    rule namespace() -> ast::Namespace
+        =
+            begin:position!()
+            r#pub:("pub" _)?
+            "namespace" _ id:id() _? "{" body:block_body() ___? "}"
+            end:position!()
+        { ast::Namespace::new(span!(begin, end), r#pub.is_some(), id, body) }
+
     rule function_param() -> ast::function::Param
         =
             begin:position!()
             id:id() _?
             ":" __?
-            r#type:qualifier()
+            r#type:type_expr()
             end:position!()
         { ast::function::Param::new(span!(begin, end), id, r#type) }
 
+    /// A function declaration shorthand, i.e. `.` or `::`.
+    rule function_shorthand() -> ast::function::Shorthand
+        = "::" { ast::function::Shorthand::Static }
+        / "." { ast::function::Shorthand::Instance }
+
     /// A function declaration.
     rule function_decl() -> ast::function::Decl
         =
             begin:position!()
             r#pub:("pub" _)?
+            r#extern:("extern" _)?
             "fn" _
+            shorthand:function_shorthand()?
             id:qualifier() _?
-            "(" ___? params:function_param() ** ("," ___?) ")" _?
-            "->" __? return_type:qualifier()
-            term()
+            "(" ___? params:function_param() ** ("," ___?)
+            variadic:(
+                sep:("," ___?)? "..." {?
+                    if sep.is_some() != params.is_empty() { Ok(()) } else { Err("`)`") }
+                }
+            )?
+            ")" _?
+            return_type:("->" __? it:type_expr() _? { it })?
+            throws:("throws" _ it:(qualifier() ++ ("," _?)) _? { it })?
+            body:(function_body() / term() { None })
             end:position!()
         {
             ast::function::Decl::new(
                 span!(begin, end),
                 r#pub.is_some(),
+                r#extern.is_some(),
+                shorthand,
                 id,
                 params,
-                return_type
+                variadic.is_some(),
+                return_type,
+                throws.unwrap_or_default(),
+                body,
             )
         }
 
+    rule function_body() -> Option<Vec<ast::BlockBody>>
+        = "{" body:block_body() ___? "}" { Some(body) }
+
+    /// A `return` statement.
+    rule r#return() -> ast::Return
+        =
+            begin:position!()
+            "return" expr:(_ it:expr() { it })? term()
+            end:position!()
+        { ast::Return::new(span!(begin, end), expr) }
+
+    /// A `throw` statement.
+    rule throw() -> ast::Throw
+        =
+            begin:position!()
+            "throw" _ error:qualifier() term()
+            end:position!()
+        { ast::Throw::new(span!(begin, end), error) }
+
+    /// An `if` statement, optionally followed by `else` or `else if`.
+    rule r#if() -> ast::If
+        =
+            begin:position!()
+            "if" _ condition:if_condition() _? "{" then:block_body() ___? "}"
+            r#else:(___? "else" _? it:else_body() { it })?
+            end:position!()
+        { ast::If::new(span!(begin, end), condition, then, r#else) }
+
+    rule if_condition() -> ast::Condition
+        = "let" _ id:id() _? "=" __? expr:expr() { ast::Condition::Let(id, expr) }
+        / expr:expr() { ast::Condition::Expr(expr) }
+
+    rule else_body() -> Vec<ast::BlockBody>
+        = it:r#if() { vec![ast::BlockBody::Stmt(ast::Statement::If(it))] }
+        / "{" body:block_body() ___? "}" { body }
+
+    /// An error declaration, e.g. `error NotFound`.
+    rule error_decl() -> ast::error::Decl
+        =
+            begin:position!()
+            r#pub:("pub" _)?
+            "error" _ id:id() term()
+            end:position!()
+        { ast::error::Decl::new(span!(begin, end), r#pub.is_some(), id) }
+
     /// A statement.
     rule statement() -> ast::Statement
         = it:var_decl()      { ast::Statement::VarDecl(it) }
         / it:import()        { ast::Statement::Import(it) }
+        / it:c_import()      { ast::Statement::CImport(it) }
         / it:decorator()     { ast::Statement::Decorator(it) }
         / it:struct_def()    { ast::Statement::StructDef(it) }
+        / it:struct_impl()   { ast::Statement::StructImpl(it) }
+        / it:namespace()     { ast::Statement::Namespace(it) }
         / it:function_decl() { ast::Statement::FunctionDecl(it) }
+        / it:r#return()      { ast::Statement::Return(it) }
+        / it:throw()         { ast::Statement::Throw(it) }
+        / it:r#if()          { ast::Statement::If(it) }
+        / it:error_decl()    { ast::Statement::ErrorDecl(it) }
         / terminated_expr()
 
     rule block_body_el() -> ast::BlockBody
@@ -241,6 +572,7 @@ mod test {
             body: vec![
                 ast::BlockBody::Stmt(ast::Statement::VarDecl(ast::VarDecl::new(
                     span!(0, 13),
+                    false,
                     ast::Id::new(span!(4, 5), "x".to_string()),
                     ast::Expr::BoolLiteral(ast::literal::Bool::new(span!(8, 12), true)),
                 ))),
@@ -296,13 +628,117 @@ mod test {
                 ast::Import::new(
                     span!(0, 29),
                     true,
-                    vec![ast::Id::new(span!(13, 16), "Foo".to_string())],
+                    ast::Imported::Ids(vec![ast::ImportId::new(
+                        span!(13, 16),
+                        ast::Qualifier::from_string(span!(13, 16), "Foo".to_string()),
+                        None,
+                    )]),
                     ast::literal::String::new(span!(24, 29), "bar".to_string()),
                 ),
             ))],
         };
 
         assert_eq!(parse_simple(input), ast);
+
+        let import = |input: &str| match &parse_simple(input).body[0] {
+            ast::BlockBody::Stmt(ast::Statement::Import(i)) => i.imported.to_string(),
+            _ => panic!("expected an import"),
+        };
+
+        assert_eq!(
+            import(r#"import { Foo as Bar, Net::Http as `Http 2` } from "bar""#),
+            "{`Foo` as `Bar`, `Net`::`Http` as `Http 2`}"
+        );
+
+        assert_eq!(import(r#"import * as bar from "bar""#), "* as `bar`");
+        assert_eq!(import(r#"pub import * from "bar""#), "*");
+    }
+
+    #[test]
+    pub fn test_print() {
+        let input = r#"@println("x = {}", x)"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Expr(ast::Expr::MacroCall(
+                ast::MacroCall::new(
+                    span!(0, 21),
+                    ast::Id::new(span!(1, 8), "println".to_string()),
+                    vec![
+                        ast::Expr::Interpolation(ast::Interpolation::new(
+                            span!(9, 17),
+                            vec![
+                                ast::interpolation::Segment::Text("x = ".to_string()),
+                                ast::interpolation::Segment::Placeholder(span!(14, 16)),
+                            ],
+                        )),
+                        ast::Expr::Ref(ast::Qualifier::from_string(span!(19, 20), "x".to_string())),
+                    ],
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_interpolation() {
+        let input = r#"let s = "a {x} {{b}}""#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::VarDecl(
+                ast::VarDecl::new(
+                    span!(0, 21),
+                    false,
+                    ast::Id::new(span!(4, 5), "s".to_string()),
+                    ast::Expr::Interpolation(ast::Interpolation::new(
+                        span!(8, 21),
+                        vec![
+                            ast::interpolation::Segment::Text("a ".to_string()),
+                            ast::interpolation::Segment::Expr(ast::Expr::Ref(
+                                ast::Qualifier::from_string(span!(12, 13), "x".to_string()),
+                            )),
+                            ast::interpolation::Segment::Text(" {b}".to_string()),
+                        ],
+                    )),
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+
+        let input = r#"let s = "{{}}""#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::VarDecl(
+                ast::VarDecl::new(
+                    span!(0, 14),
+                    false,
+                    ast::Id::new(span!(4, 5), "s".to_string()),
+                    ast::Expr::StringLiteral(ast::literal::String::new(
+                        span!(8, 14),
+                        "{}".to_string(),
+                    )),
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_c_import() {
+        let input = r#"@cimport("stdio.h")"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::CImport(
+                ast::CImport::new(
+                    span!(0, 19),
+                    ast::literal::String::new(span!(9, 18), "stdio.h".to_string()),
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
     }
 
     #[test]
@@ -311,7 +747,25 @@ mod test {
 
         let ast = ast::Mod {
             body: vec![ast::BlockBody::Stmt(ast::Statement::Decorator(
-                ast::Decorator::new(span!(0, 6), ast::Id::new(span!(2, 5), "Foo".to_string())),
+                ast::Decorator::new(
+                    span!(0, 6),
+                    ast::Id::new(span!(2, 5), "Foo".to_string()),
+                    vec![],
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+
+        let input = r#"@[Foo("bar")]"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::Decorator(
+                ast::Decorator::new(
+                    span!(0, 13),
+                    ast::Id::new(span!(2, 5), "Foo".to_string()),
+                    vec![ast::literal::String::new(span!(6, 11), "bar".to_string())],
+                ),
             ))],
         };
 
@@ -328,6 +782,8 @@ mod test {
                     span!(0, 19),
                     true,
                     ast::Id::new(span!(12, 15), "Foo".to_string()),
+                    vec![],
+                    vec![],
                 ),
             ))],
         };
@@ -344,20 +800,34 @@ mod test {
                 ast::function::Decl::new(
                     span!(0, 36),
                     true,
+                    false,
+                    None,
                     ast::Qualifier::from_string(span!(7, 10), "eq?".to_string()),
                     vec![
                         ast::function::Param::new(
                             span!(11, 18),
                             ast::Id::new(span!(11, 12), "a".to_string()),
-                            ast::Qualifier::from_string(span!(14, 18), "Bool".to_string()),
+                            ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                                span!(14, 18),
+                                "Bool".to_string(),
+                            )),
                         ),
                         ast::function::Param::new(
                             span!(20, 27),
                             ast::Id::new(span!(20, 21), "b".to_string()),
-                            ast::Qualifier::from_string(span!(23, 27), "Bool".to_string()),
+                            ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                                span!(23, 27),
+                                "Bool".to_string(),
+                            )),
                         ),
                     ],
-                    ast::Qualifier::from_string(span!(32, 36), "Bool".to_string()),
+                    false,
+                    Some(ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                        span!(32, 36),
+                        "Bool".to_string(),
+                    ))),
+                    vec![],
+                    None,
                 ),
             ))],
         };
@@ -374,9 +844,17 @@ mod test {
                 ast::function::Decl::new(
                     span!(0, 15),
                     false,
+                    false,
+                    None,
                     ast::Qualifier::from_string(span!(3, 6), "foo".to_string()),
                     vec![],
-                    ast::Qualifier::from_string(span!(12, 15), "Bar".to_string()),
+                    false,
+                    Some(ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                        span!(12, 15),
+                        "Bar".to_string(),
+                    ))),
+                    vec![],
+                    None,
                 ),
             ))],
         };
@@ -392,6 +870,7 @@ mod test {
             body: vec![ast::BlockBody::Expr(ast::Expr::FunctionCall(
                 ast::Call::new(
                     span!(0, 10),
+                    None,
                     ast::Qualifier::from_string(span!(0, 1), "x".to_string()),
                     vec![
                         ast::Expr::Ref(ast::Qualifier::from_string(span!(2, 3), "y".to_string())),
@@ -403,4 +882,497 @@ mod test {
 
         assert_eq!(parse_simple(input), ast);
     }
+
+    #[test]
+    pub fn test_struct_function_decl() {
+        let input = r#"struct Foo { fn .bar(baz: Self) -> Self }"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::StructDef(
+                ast::r#struct::Def::new(
+                    span!(0, 41),
+                    false,
+                    ast::Id::new(span!(7, 10), "Foo".to_string()),
+                    vec![],
+                    vec![ast::BlockBody::Stmt(ast::Statement::FunctionDecl(
+                        ast::function::Decl::new(
+                            span!(13, 40),
+                            false,
+                            false,
+                            Some(ast::function::Shorthand::Instance),
+                            ast::Qualifier::from_string(span!(17, 20), "bar".to_string()),
+                            vec![ast::function::Param::new(
+                                span!(21, 30),
+                                ast::Id::new(span!(21, 24), "baz".to_string()),
+                                ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                                    span!(26, 30),
+                                    "Self".to_string(),
+                                )),
+                            )],
+                            false,
+                            Some(ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                                span!(35, 39),
+                                "Self".to_string(),
+                            ))),
+                            vec![],
+                            None,
+                        ),
+                    ))],
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_static_function_decl() {
+        let input = r#"fn ::foo(self: Bar) -> Bar"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::FunctionDecl(
+                ast::function::Decl::new(
+                    span!(0, 26),
+                    false,
+                    false,
+                    Some(ast::function::Shorthand::Static),
+                    ast::Qualifier::from_string(span!(5, 8), "foo".to_string()),
+                    vec![ast::function::Param::new(
+                        span!(9, 18),
+                        ast::Id::new(span!(9, 13), "self".to_string()),
+                        ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                            span!(15, 18),
+                            "Bar".to_string(),
+                        )),
+                    )],
+                    false,
+                    Some(ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                        span!(23, 26),
+                        "Bar".to_string(),
+                    ))),
+                    vec![],
+                    None,
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_instance_call() {
+        let input = r#"x.eq?(y)"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Expr(ast::Expr::FunctionCall(
+                ast::Call::new(
+                    span!(0, 8),
+                    Some(ast::Expr::Ref(ast::Qualifier::from_string(
+                        span!(0, 1),
+                        "x".to_string(),
+                    ))),
+                    ast::Qualifier::from_string(span!(2, 5), "eq?".to_string()),
+                    vec![ast::Expr::Ref(ast::Qualifier::from_string(
+                        span!(6, 7),
+                        "y".to_string(),
+                    ))],
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_function_def() {
+        let input = r#"fn foo(a: Bool) -> Bool { return a }"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::FunctionDecl(
+                ast::function::Decl::new(
+                    span!(0, 36),
+                    false,
+                    false,
+                    None,
+                    ast::Qualifier::from_string(span!(3, 6), "foo".to_string()),
+                    vec![ast::function::Param::new(
+                        span!(7, 14),
+                        ast::Id::new(span!(7, 8), "a".to_string()),
+                        ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                            span!(10, 14),
+                            "Bool".to_string(),
+                        )),
+                    )],
+                    false,
+                    Some(ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                        span!(19, 23),
+                        "Bool".to_string(),
+                    ))),
+                    vec![],
+                    Some(vec![ast::BlockBody::Stmt(ast::Statement::Return(
+                        ast::Return::new(
+                            span!(26, 35),
+                            Some(ast::Expr::Ref(ast::Qualifier::from_string(
+                                span!(33, 34),
+                                "a".to_string(),
+                            ))),
+                        ),
+                    ))]),
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_struct_impl() {
+        let input = r#"impl Foo { fn bar() { } }"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::StructImpl(
+                ast::r#struct::Impl::new(
+                    span!(0, 25),
+                    ast::Qualifier::from_string(span!(5, 8), "Foo".to_string()),
+                    vec![ast::BlockBody::Stmt(ast::Statement::FunctionDecl(
+                        ast::function::Decl::new(
+                            span!(11, 23),
+                            false,
+                            false,
+                            None,
+                            ast::Qualifier::from_string(span!(14, 17), "bar".to_string()),
+                            vec![],
+                            false,
+                            None,
+                            vec![],
+                            Some(vec![]),
+                        ),
+                    ))],
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_namespace() {
+        let input = r#"pub namespace Net { fn get() { } }"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::Namespace(
+                ast::Namespace::new(
+                    span!(0, 34),
+                    true,
+                    ast::Id::new(span!(14, 17), "Net".to_string()),
+                    vec![ast::BlockBody::Stmt(ast::Statement::FunctionDecl(
+                        ast::function::Decl::new(
+                            span!(20, 32),
+                            false,
+                            false,
+                            None,
+                            ast::Qualifier::from_string(span!(23, 26), "get".to_string()),
+                            vec![],
+                            false,
+                            None,
+                            vec![],
+                            Some(vec![]),
+                        ),
+                    ))],
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_qualified_call() {
+        let input = r#"Net::Http::get()"#;
+
+        let net = ast::Qualifier::from_string(span!(0, 3), "Net".to_string());
+
+        let http = ast::Qualifier::new(
+            span!(0, 9),
+            Some(net),
+            ast::Id::new(span!(5, 9), "Http".to_string()),
+        );
+
+        let get = ast::Qualifier::new(
+            span!(0, 14),
+            Some(http),
+            ast::Id::new(span!(11, 14), "get".to_string()),
+        );
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Expr(ast::Expr::FunctionCall(
+                ast::Call::new(span!(0, 16), None, get, vec![]),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_wrapped_id() {
+        let input = r#"let `fn` = true"#;
+
+        let ast = parse_simple(input);
+
+        match &ast.body[0] {
+            ast::BlockBody::Stmt(ast::Statement::VarDecl(decl)) => {
+                assert_eq!(decl.id.value, "fn");
+                assert!(decl.id.wrapped);
+            }
+            _ => panic!("expected a variable declaration"),
+        }
+
+        assert!(onyx_parser::start(r#"let fn = true"#).is_err());
+        assert!(onyx_parser::start(r#"let fnord = true"#).is_ok());
+    }
+
+    #[test]
+    pub fn test_function_type() {
+        let input = r#"fn foo(f: fn(Bool) -> Bool);"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Stmt(ast::Statement::FunctionDecl(
+                ast::function::Decl::new(
+                    span!(0, 28),
+                    false,
+                    false,
+                    None,
+                    ast::Qualifier::from_string(span!(3, 6), "foo".to_string()),
+                    vec![ast::function::Param::new(
+                        span!(7, 26),
+                        ast::Id::new(span!(7, 8), "f".to_string()),
+                        ast::TypeExpr::Function {
+                            span: span!(10, 26),
+                            params: vec![ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                                span!(13, 17),
+                                "Bool".to_string(),
+                            ))],
+                            return_type: Some(Box::new(ast::TypeExpr::Ref(
+                                ast::Qualifier::from_string(span!(22, 26), "Bool".to_string()),
+                            ))),
+                        },
+                    )],
+                    false,
+                    None,
+                    vec![],
+                    None,
+                ),
+            ))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_closure() {
+        let input = r#"fn [x, &y](a: Bool) { }"#;
+
+        let ast = ast::Mod {
+            body: vec![ast::BlockBody::Expr(ast::Expr::Closure(ast::Closure::new(
+                span!(0, 23),
+                vec![
+                    ast::Capture::new(
+                        span!(4, 5),
+                        ast::Id::new(span!(4, 5), "x".to_string()),
+                        false,
+                    ),
+                    ast::Capture::new(
+                        span!(7, 9),
+                        ast::Id::new(span!(8, 9), "y".to_string()),
+                        true,
+                    ),
+                ],
+                vec![ast::function::Param::new(
+                    span!(11, 18),
+                    ast::Id::new(span!(11, 12), "a".to_string()),
+                    ast::TypeExpr::Ref(ast::Qualifier::from_string(
+                        span!(14, 18),
+                        "Bool".to_string(),
+                    )),
+                )],
+                None,
+                vec![],
+            )))],
+        };
+
+        assert_eq!(parse_simple(input), ast);
+    }
+
+    #[test]
+    pub fn test_extern_function_decl() {
+        let input = r#"extern fn printf(format: CInt, ...) -> CInt"#;
+
+        match &parse_simple(input).body[0] {
+            ast::BlockBody::Stmt(ast::Statement::FunctionDecl(decl)) => {
+                assert!(decl.r#extern);
+                assert!(decl.variadic);
+                assert_eq!(decl.params.len(), 1);
+                assert!(decl.body.is_none());
+            }
+            _ => panic!("expected a function declaration"),
+        }
+
+        assert!(onyx_parser::start(r#"extern fn printf(...)"#).is_ok());
+        assert!(onyx_parser::start(r#"extern fn printf(, ...)"#).is_err());
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let input = r#"pub error NotFound
+fn find() -> Bool throws NotFound, Net::Timeout {
+  throw NotFound
+}
+let x = find() catch false
+fn retry() -> Bool throws NotFound { return try find() }"#;
+
+        let body = parse_simple(input).body;
+
+        match &body[0] {
+            ast::BlockBody::Stmt(ast::Statement::ErrorDecl(decl)) => {
+                assert!(decl.r#pub);
+                assert_eq!(decl.id.value, "NotFound");
+            }
+            _ => panic!("expected an error declaration"),
+        }
+
+        match &body[1] {
+            ast::BlockBody::Stmt(ast::Statement::FunctionDecl(decl)) => {
+                assert_eq!(decl.throws.len(), 2);
+                assert_eq!(decl.throws[1].to_string(), "`Net`::`Timeout`");
+
+                assert!(matches!(
+                    &decl.body.as_ref().unwrap()[0],
+                    ast::BlockBody::Stmt(ast::Statement::Throw(_))
+                ));
+            }
+            _ => panic!("expected a function declaration"),
+        }
+
+        match &body[2] {
+            ast::BlockBody::Stmt(ast::Statement::VarDecl(var)) => {
+                assert_eq!(var.expr.to_string(), "`find`() catch false");
+                assert!(matches!(var.expr, ast::Expr::Catch(_)));
+            }
+            _ => panic!("expected a variable declaration"),
+        }
+
+        match &body[3] {
+            ast::BlockBody::Stmt(ast::Statement::FunctionDecl(decl)) => {
+                assert!(matches!(
+                    &decl.body.as_ref().unwrap()[0],
+                    ast::BlockBody::Stmt(ast::Statement::Return(ast::Return {
+                        expr: Some(ast::Expr::Try(_)),
+                        ..
+                    }))
+                ));
+            }
+            _ => panic!("expected a function declaration"),
+        }
+
+        assert!(onyx_parser::start(r#"fn foo() throws {}"#).is_err());
+    }
+
+    #[test]
+    pub fn test_optionals() {
+        let input = r#"fn foo(x: Bool?, y: Net::Response??) -> fn(Bool?) -> Bool? {
+  if x != nil {
+  } else if let z = y {
+  }
+}"#;
+
+        match &parse_simple(input).body[0] {
+            ast::BlockBody::Stmt(ast::Statement::FunctionDecl(decl)) => {
+                assert_eq!(decl.params[0].r#type.to_string(), "`Bool`?");
+                assert_eq!(decl.params[1].r#type.to_string(), "`Net`::`Response`??");
+
+                assert_eq!(
+                    decl.return_type.as_ref().unwrap().to_string(),
+                    "fn(`Bool`?) -> `Bool`?"
+                );
+
+                match &decl.body.as_ref().unwrap()[0] {
+                    ast::BlockBody::Stmt(ast::Statement::If(r#if)) => {
+                        assert_eq!(r#if.condition.to_string(), "`x` != nil");
+                        let r#else = r#if.r#else.as_ref().unwrap();
+
+                        assert!(matches!(
+                            &r#else[0],
+                            ast::BlockBody::Stmt(ast::Statement::If(ast::If {
+                                condition: ast::Condition::Let(..),
+                                ..
+                            }))
+                        ));
+                    }
+                    _ => panic!("expected an if statement"),
+                }
+            }
+            _ => panic!("expected a function declaration"),
+        }
+
+        // An id may still end with `?` outside of types.
+        assert!(onyx_parser::start(r#"let x = nil?; x = nil"#).is_err());
+        assert!(onyx_parser::start(r#"let ok? = x == nil"#).is_ok());
+    }
+
+    #[test]
+    pub fn test_pointer_and_tuple_types() {
+        let input = r#"fn foo(x: *CInt, y: (Bool, *CInt?), z: (Bool)) -> *(Bool, CInt);"#;
+
+        match &parse_simple(input).body[0] {
+            ast::BlockBody::Stmt(ast::Statement::FunctionDecl(decl)) => {
+                assert_eq!(decl.params[0].r#type.to_string(), "*`CInt`");
+                assert_eq!(decl.params[1].r#type.to_string(), "(`Bool`, *`CInt`?)");
+
+                // A single parenthesized type is not a tuple.
+                assert!(matches!(decl.params[2].r#type, ast::TypeExpr::Ref(_)));
+
+                assert_eq!(
+                    decl.return_type.as_ref().unwrap().to_string(),
+                    "*(`Bool`, `CInt`)"
+                );
+            }
+            _ => panic!("expected a function declaration"),
+        }
+    }
+
+    #[test]
+    pub fn test_generic_struct() {
+        let input = r#"struct Pair<A, B> { }
+fn swap(pair: Pair<Bool, Pair<CInt, Bool>?>) -> Pair<Bool, Bool>;"#;
+
+        let body = parse_simple(input).body;
+
+        match &body[0] {
+            ast::BlockBody::Stmt(ast::Statement::StructDef(def)) => {
+                assert_eq!(def.to_string(), "struct `Pair`<`A`, `B`> {}");
+            }
+            _ => panic!("expected a struct definition"),
+        }
+
+        match &body[1] {
+            ast::BlockBody::Stmt(ast::Statement::FunctionDecl(decl)) => {
+                assert_eq!(
+                    decl.params[0].r#type.to_string(),
+                    "`Pair`<`Bool`, `Pair`<`CInt`, `Bool`>?>"
+                );
+            }
+            _ => panic!("expected a function declaration"),
+        }
+    }
+
+    #[test]
+    pub fn test_pub_var_decl() {
+        match &parse_simple("pub let x = true").body[0] {
+            ast::BlockBody::Stmt(ast::Statement::VarDecl(var)) => {
+                assert!(var.r#pub);
+                assert_eq!(var.to_string(), "pub let `x` = true");
+            }
+            _ => panic!("expected a variable declaration"),
+        }
+    }
 }
@@ -1,17 +1,64 @@
-use crate::{unit::Unit, Panic};
+use crate::{header, lower, package::Package, unit::Unit, Location, Panic};
 use std::{
     cell::RefCell,
     fs::create_dir_all,
     io::{self, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::Command,
     rc::Rc,
 };
 
+/// The kind of a compiled program artifact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputKind {
+    Executable,
+
+    /// A static C library with a header.
+    StaticLibrary,
+
+    /// A shared C library with a header.
+    SharedLibrary,
+}
+
+/// A Zig optimization mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Optimize {
+    #[default]
+    Debug,
+    ReleaseSafe,
+    ReleaseFast,
+    ReleaseSmall,
+}
+
+impl Optimize {
+    /// Return the Zig `-O` argument.
+    fn zig_arg(self) -> &'static str {
+        match self {
+            Optimize::Debug => "Debug",
+            Optimize::ReleaseSafe => "ReleaseSafe",
+            Optimize::ReleaseFast => "ReleaseFast",
+            Optimize::ReleaseSmall => "ReleaseSmall",
+        }
+    }
+}
+
 pub struct Program {
     cache_path: PathBuf,
     cache_dir_ensured: bool,
     units: Vec<Rc<RefCell<Unit>>>,
+
+    /// Directories to look bare import specifiers up in, in order.
+    pub search_paths: Vec<PathBuf>,
+
+    /// Resolved packages, the root one first.
+    pub packages: Vec<Rc<Package>>,
+
+    pub optimize: Optimize,
+
+    /// Paths of units being resolved, outermost first,
+    /// each with the location of the import which led to it.
+    resolving: Vec<(PathBuf, Option<Location>)>,
 }
 
 impl Program {
@@ -20,6 +67,10 @@ impl Program {
             cache_path,
             cache_dir_ensured: false,
             units: Vec::new(),
+            search_paths: Vec::new(),
+            packages: Vec::new(),
+            optimize: Optimize::default(),
+            resolving: Vec::new(),
         }))
     }
 
@@ -27,25 +78,80 @@ impl Program {
     /// Adds the unit to the program if it doesn't exist.
     /// The very first resolved unit becomes the program entry unit.
     pub fn resolve(this: Rc<RefCell<Self>>, path: PathBuf) -> Result<Rc<RefCell<Unit>>, Panic> {
+        Self::resolve_from(this, path, None)
+    }
+
+    /// Resolve a unit at `path` imported at `import`.
+    /// Onyx-panics if the import closes a cycle.
+    pub fn resolve_import(
+        this: Rc<RefCell<Self>>,
+        path: PathBuf,
+        import: Location,
+    ) -> Result<Rc<RefCell<Unit>>, Panic> {
+        Self::resolve_from(this, path, Some(import))
+    }
+
+    fn resolve_from(
+        this: Rc<RefCell<Self>>,
+        path: PathBuf,
+        import: Option<Location>,
+    ) -> Result<Rc<RefCell<Unit>>, Panic> {
         for unit in &this.as_ref().borrow().units {
             if unit.as_ref().borrow().path == path {
                 return Ok(Rc::clone(unit));
             }
         }
 
+        if let Some(panic) = this.as_ref().borrow().find_cycle(&path, &import) {
+            return Err(panic);
+        }
+
         // Ensure the cache directory exists only once.
         if !this.as_ref().borrow().cache_dir_ensured {
             create_dir_all(&this.as_ref().borrow().cache_path).unwrap();
             this.as_ref().borrow_mut().cache_dir_ensured = true;
         }
 
-        let unit = Unit::new(Rc::downgrade(&this), path);
-        Unit::resolve(unit.clone())?;
+        let unit = Unit::new(Rc::downgrade(&this), path.clone());
+
+        this.borrow_mut().resolving.push((path, import));
+        let result = Unit::resolve(unit.clone());
+        this.borrow_mut().resolving.pop();
+
+        result?;
         this.borrow_mut().units.push(unit.clone());
 
         Ok(unit)
     }
 
+    /// Return a panic tracing the import cycle if `path` is being resolved.
+    fn find_cycle(&self, path: &Path, import: &Option<Location>) -> Option<Panic> {
+        let start = self.resolving.iter().position(|(p, _)| p == path)?;
+
+        let mut panic = Panic::new(
+            format!("Cyclic import of \"{}\"", path.display()),
+            import.clone(),
+        );
+
+        let edges = self.resolving[start..]
+            .windows(2)
+            .map(|w| (&w[0].0, &w[1].0, &w[1].1))
+            .chain(std::iter::once((
+                &self.resolving.last().unwrap().0,
+                &self.resolving[start].0,
+                import,
+            )));
+
+        for (from, to, location) in edges {
+            panic.add_note(
+                format!("\"{}\" imports \"{}\"", from.display(), to.display()),
+                location.clone(),
+            );
+        }
+
+        Some(panic)
+    }
+
     pub fn run(
         this: Rc<RefCell<Self>>,
         input_path: PathBuf,
@@ -63,6 +169,8 @@ impl Program {
             "-lc",
             "--cache-dir",
             zig_cache_path.as_path().to_str().unwrap(),
+            "-O",
+            this.as_ref().borrow().optimize.zig_arg(),
         ]);
         dbg!(&cmd);
 
@@ -82,6 +190,7 @@ impl Program {
         this: Rc<RefCell<Self>>,
         input_path: PathBuf,
         output_path: PathBuf,
+        output_kind: OutputKind,
         zig_path: PathBuf,
     ) -> Result<(), Panic> {
         let entry_unit_lowered_path = Self::lower(Rc::clone(&this), input_path)?;
@@ -90,13 +199,21 @@ impl Program {
         zig_cache_path.push("./zig");
 
         let mut cmd = Command::new(zig_path.as_path());
+
+        match output_kind {
+            OutputKind::Executable => cmd.arg("build-exe"),
+            OutputKind::StaticLibrary => cmd.arg("build-lib"),
+            OutputKind::SharedLibrary => cmd.args(["build-lib", "-dynamic"]),
+        };
+
         cmd.args([
-            "build-exe",
             entry_unit_lowered_path.as_path().to_str().unwrap(),
             "-lc",
             "--cache-dir",
             zig_cache_path.as_path().to_str().unwrap(),
             ("-femit-bin=".to_string() + output_path.as_path().to_str().unwrap()).as_str(),
+            "-O",
+            this.as_ref().borrow().optimize.zig_arg(),
         ]);
         dbg!(&cmd);
 
@@ -109,18 +226,142 @@ impl Program {
             panic!("Failed to compile {}", entry_unit_lowered_path.display());
         }
 
+        if output_kind != OutputKind::Executable {
+            Self::write_header(&this.as_ref().borrow(), &output_path.with_extension("h"));
+        }
+
         Ok(())
     }
 
+    /// Write a C header for the exported program symbols at `path`.
+    fn write_header(&self, path: &Path) {
+        let stem = path.file_stem().unwrap().to_string_lossy();
+        let guard: String = stem
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        let mut file = std::fs::File::create(path).unwrap();
+        header::write(&self.units, &format!("{}_H", guard), &mut file).unwrap();
+    }
+
+    /// Return units in the order to initialize them in, i.e. topologically
+    /// with dependencies first; the entry unit is the last.
+    /// Units are added once resolved, which happens after their dependencies,
+    /// and import cycles are rejected.
+    pub fn init_order(&self) -> &[Rc<RefCell<Unit>>] {
+        &self.units
+    }
+
+    /// Return the package a unit at `path` belongs to, i.e. the one with
+    /// the innermost directory containing it.
+    pub fn package_of(&self, path: &Path) -> Option<Rc<Package>> {
+        let path = path.canonicalize().ok()?;
+
+        self.packages
+            .iter()
+            .filter(|p| path.starts_with(&p.dir))
+            .max_by_key(|p| p.dir.components().count())
+            .cloned()
+    }
+
+    /// Return the paths to look a bare import specifier up at, in order.
+    ///
+    /// A specifier prefixed with a dependency name of the importing unit
+    /// package is looked up in the dependency source roots only,
+    /// e.g. `"collections/list"`; otherwise the package source roots
+    /// are searched before the search paths.
+    pub fn search_bases(&self, importer: &Path, specifier: &Path) -> Vec<PathBuf> {
+        let mut bases = Vec::new();
+
+        if let Some(package) = self.package_of(importer) {
+            let mut components = specifier.components();
+
+            if let Some(std::path::Component::Normal(first)) = components.next() {
+                let dependency = package
+                    .dependencies
+                    .iter()
+                    .find(|name| first == name.as_str())
+                    .and_then(|name| self.packages.iter().find(|p| &p.name == name));
+
+                if let Some(dependency) = dependency {
+                    let rest = components.as_path();
+                    return dependency.sources.iter().map(|s| s.join(rest)).collect();
+                }
+            }
+
+            bases.extend(package.sources.iter().map(|s| s.join(specifier)));
+        }
+
+        bases.extend(self.search_paths.iter().map(|dir| dir.join(specifier)));
+        bases
+    }
+
+    /// Return the name of a module at `path`, which is prefixed with its
+    /// package name if any, e.g. `collections/list`, or the path sans extension.
+    pub fn module_name(&self, path: &Path) -> String {
+        match self.package_of(path) {
+            Some(package) => package.module_name(&path.canonicalize().unwrap()),
+            None => path.with_extension("").display().to_string(),
+        }
+    }
+
+    /// Return the canonical path of an existing file, which identifies a unit.
+    /// The path is relative to the current directory if it is within it.
+    pub fn canonicalize(path: &Path) -> Option<PathBuf> {
+        let path = path.canonicalize().ok().filter(|p| p.is_file())?;
+
+        match std::env::current_dir().and_then(|d| d.canonicalize()) {
+            Ok(dir) => match path.strip_prefix(dir) {
+                Ok(relative) => Some(relative.to_path_buf()),
+                Err(_) => Some(path),
+            },
+            Err(_) => Some(path),
+        }
+    }
+
     /// Lower the whole program, starting with the entry unit.
     /// Returns the entry point lowered path.
     fn lower(this: Rc<RefCell<Self>>, entry_path: PathBuf) -> Result<PathBuf, Panic> {
+        // A missing entry file is reported when the unit is read.
+        let entry_path = Self::canonicalize(&entry_path).unwrap_or(entry_path);
         let entry = Self::resolve(Rc::clone(&this), entry_path)?;
 
+        let runtime_path = this.as_ref().borrow().cache_path.join(lower::RUNTIME);
+        std::fs::write(runtime_path, lower::RUNTIME_SOURCE).unwrap();
+
+        let cache_path = this.as_ref().borrow().cache_path.clone();
+
+        let units: Vec<_> = this
+            .as_ref()
+            .borrow()
+            .init_order()
+            .iter()
+            .filter(|unit| !Rc::ptr_eq(unit, &entry))
+            .cloned()
+            .collect();
+
+        // Some units are not imported explicitly, e.g. builtin ones,
+        // thus lower each unit initialized by the entry point.
+        for unit in &units {
+            unit.as_ref().borrow_mut().lower(cache_path.clone(), None);
+        }
+
+        let init_order: Vec<String> = units
+            .iter()
+            .map(|unit| unit.as_ref().borrow().hash())
+            .collect();
+
         let path = entry
             .as_ref()
             .borrow_mut()
-            .lower(this.as_ref().borrow().cache_path.clone());
+            .lower(cache_path, Some(&init_order));
 
         Ok(path)
     }
@@ -4,13 +4,17 @@ use std::rc::Weak;
 
 use crate::ast;
 use crate::dst;
+use crate::dst::HasId;
 use crate::dst::InferType;
 use crate::dst::Scope;
 use crate::location::HasSpan;
+use crate::location::Span;
+use crate::lower::MANGLE_SEPARATOR;
 use crate::panic::Panic;
 use crate::unit::Unit;
 use crate::Location;
 
+mod initialization;
 mod qualifier;
 
 pub trait Resolve<T> {
@@ -18,76 +22,89 @@ pub trait Resolve<T> {
 }
 
 impl ast::Mod {
+    /// Resolves in four passes, so that declarations may be referenced
+    /// before they appear: imports and types, then function signatures,
+    /// then module-level variables, then function bodies
+    /// and `main` statements in order. Finally, ensures no variable
+    /// is read before its initialization.
     pub fn resolve(&self, unit: Weak<RefCell<Unit>>) -> Result<dst::Mod, Panic> {
         let mut dst_module = dst::Mod::new(unit);
 
+        for body in &self.body {
+            if let ast::BlockBody::Stmt(stmt) = body {
+                match stmt {
+                    ast::Statement::Import(i) => resolve_import(i, &mut dst_module)?,
+                    ast::Statement::CImport(i) => {
+                        let header = resolve_c_header(&i.header, &dst_module)?;
+
+                        if !dst_module.c_imports.contains(&header) {
+                            dst_module.c_imports.push(header);
+                        }
+                    }
+                    _ => declare_types(stmt, &mut dst_module)?,
+                }
+            }
+        }
+
+        let mut pending = Vec::new();
+
+        for body in &self.body {
+            if let ast::BlockBody::Stmt(stmt) = body {
+                declare_functions(stmt, &mut dst_module, &mut pending)?;
+            }
+        }
+
+        // Variables are declared before function bodies so that functions
+        // may refer to any of them; initializers see preceding variables only.
+        let mut globals = Vec::new();
+
+        for body in &self.body {
+            if let ast::BlockBody::Stmt(ast::Statement::VarDecl(var_decl)) = body {
+                globals.push(declare_global(var_decl, &mut dst_module)?);
+            }
+        }
+
+        let mut globals = globals.into_iter();
+
         for body in &self.body {
             match body {
                 ast::BlockBody::Stmt(stmt) => match stmt {
                     ast::Statement::VarDecl(var_decl) => {
-                        let var = var_decl.resolve(&mut dst_module)?;
-                        dst_module.store(dst::Exportable::VarDecl(Rc::clone(&var)))?;
+                        ensure_reachable(&dst_module.main, var_decl.span(), &dst_module)?;
+                        let var = globals.next().expect("Variable must be declared");
                         dst_module.main.push(dst::Statement::VarDecl(var));
                     }
                     ast::Statement::TerminatedExpr(expr) => {
+                        ensure_reachable(&dst_module.main, expr.span(), &dst_module)?;
                         let expr = expr.resolve(&mut dst_module)?;
                         dst_module.main.push(dst::Statement::TerminatedExpr(expr));
                     }
-                    ast::Statement::Import(i) => {
-                        let dep = dst_module.resolve_dependency(i.from.clone())?;
-
-                        for id in &i.ids {
-                            let export = dep
-                                .as_ref()
-                                .borrow()
-                                .dst
-                                .as_ref()
-                                .unwrap()
-                                .search(id)
-                                .ok_or_else(|| {
-                                    Panic::new(
-                                        format!("{} not found in {}", id, i.from),
-                                        Some(Location::new(dst_module.unit(), id.span())),
-                                    )
-                                })?;
-
-                            if i.r#pub {
-                                dst_module.exports.insert(id.value.clone(), export.clone());
-                            }
-
-                            dst_module.imports.insert(id.value.clone(), export);
-                        }
-                    }
-                    ast::Statement::Decorator(d) => {
-                        let decorator = d.resolve(&mut dst_module)?;
-                        dst_module.push_decorator(decorator);
+                    ast::Statement::Return(r) => {
+                        return Err(Panic::new(
+                            "Cannot return outside of a function".to_string(),
+                            Some(Location::new(dst_module.unit(), r.span())),
+                        ));
                     }
-                    ast::Statement::StructDef(def) => {
-                        let decl = def.resolve(&mut dst_module)?;
-                        dst_module.store(dst::Exportable::StructDecl(Rc::clone(&decl)))?;
-
-                        if def.r#pub {
-                            dst_module
-                                .exports
-                                .insert(def.id.value.clone(), dst::Exportable::StructDecl(decl));
-                        }
+                    ast::Statement::Throw(t) => {
+                        return Err(Panic::new(
+                            "Cannot throw outside of a function".to_string(),
+                            Some(Location::new(dst_module.unit(), t.span())),
+                        ));
                     }
-                    ast::Statement::FunctionDecl(decl) => {
-                        let dst = decl.resolve(&mut dst_module)?;
-                        dst_module.store(dst::Exportable::FunctionDecl(Rc::clone(&dst)))?;
-
-                        if decl.r#pub {
-                            dst_module.exports.insert(
-                                decl.id.id.value.clone(),
-                                dst::Exportable::FunctionDecl(dst),
-                            );
-                        }
+                    ast::Statement::If(r#if) => {
+                        ensure_reachable(&dst_module.main, r#if.span(), &dst_module)?;
+                        let (r#if, _) = resolve_if(r#if, &mut dst_module)?;
+                        dst_module.main.push(dst::Statement::If(Box::new(r#if)));
                     }
+                    _ => define_functions(stmt, &mut dst_module, &mut pending)?,
                 },
                 ast::BlockBody::Expr(expr) => {
+                    ensure_reachable(&dst_module.main, expr.span(), &dst_module)?;
                     let expr = expr.resolve(&mut dst_module)?;
 
-                    if expr.infer_type(&dst_module).is_some() {
+                    let r#type = expr.infer_type(&dst_module);
+
+                    if !r#type.is_void() && !r#type.is_never() {
                         return Err(Panic::new(
                             "Unused expression result".to_string(),
                             Some(Location::new(dst_module.unit(), expr.span())),
@@ -102,250 +119,2270 @@ impl ast::Mod {
             }
         }
 
+        initialization::ensure_initialized(&dst_module)?;
+
         Ok(dst_module)
     }
 }
 
-impl Resolve<dst::decorator::Application> for ast::Decorator {
-    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<dst::decorator::Application, Panic> {
-        match self.id.value.as_str() {
-            "Builtin" => Ok(dst::decorator::Application::new(
-                self.clone(),
-                dst::decorator::Builtin::Builtin,
-            )),
+/// Declare a module-level variable, exporting it if it's `pub`.
+/// It's initialized in statement order when the unit is initialized.
+fn declare_global(
+    var_decl: &ast::VarDecl,
+    module: &mut dst::Mod,
+) -> Result<Rc<dst::VarDecl>, Panic> {
+    let (r#type, expr) = resolve_var_value(var_decl, module)?;
 
-            // TODO: Lookup for the decorator definition in the scope.
-            _ => Err(Panic::new(
-                format!("Unknown decorator {}", &self.id),
-                Some(Location::new(scope.unit(), self.id.span())),
-            )),
+    let mut var = dst::VarDecl::new(var_decl.clone(), r#type, expr);
+    var.unit = Some(module.unit.clone());
+    let var = Rc::new(var);
+
+    module.store(dst::Exportable::VarDecl(Rc::clone(&var)))?;
+
+    if var_decl.r#pub {
+        module.export(dst::Exportable::VarDecl(Rc::clone(&var)));
+    }
+
+    Ok(var)
+}
+
+/// Resolve an `import` statement, making the imported exports
+/// accessible from the module.
+fn resolve_import(i: &ast::Import, module: &mut dst::Mod) -> Result<(), Panic> {
+    let dep = module.resolve_dependency(i.from.clone())?;
+    let unit = module.unit();
+    let dep = dep.as_ref().borrow();
+    let dep_module = dep.dst.as_ref().unwrap();
+
+    let imported = match &i.imported {
+        ast::Imported::Ids(ids) => ids
+            .iter()
+            .map(|id| {
+                Ok((
+                    id.id().clone(),
+                    id.qualifier.lookup_export(dep_module, &unit)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Panic>>()?,
+        ast::Imported::Module(id) => vec![(
+            id.clone(),
+            dst::Exportable::Namespace(Rc::new(RefCell::new(dst::Namespace::imported(
+                id.clone(),
+                dep_module,
+            )))),
+        )],
+        ast::Imported::All => {
+            let mut all: Vec<_> = dep_module
+                .exports
+                .iter()
+                .map(|(name, export)| (ast::Id::new(i.span(), name.clone()), export.clone()))
+                .collect();
+
+            // Sort for deterministic collision errors.
+            all.sort_by(|a, b| a.0.value.cmp(&b.0.value));
+            all
         }
+    };
+
+    for (id, export) in imported {
+        module.import(&id, export, i.r#pub)?;
     }
+
+    Ok(())
 }
 
-impl Resolve<Rc<RefCell<dst::r#struct::Decl>>> for ast::r#struct::Def {
-    fn resolve(
-        &self,
-        scope: &mut dyn dst::Scope,
-    ) -> Result<Rc<RefCell<dst::r#struct::Decl>>, Panic> {
-        let mut builtin: Option<dst::r#struct::Builtin> = None;
+/// Resolve a `@cimport` header path.
+/// A relative path, e.g. `"./foo.h"`, is relative to the unit file;
+/// otherwise the header is looked up in the C include paths.
+fn resolve_c_header(header: &ast::literal::String, module: &dst::Mod) -> Result<String, Panic> {
+    if header.value.is_empty() || header.value.contains('\n') {
+        return Err(Panic::new(
+            format!("Invalid C header path {}", header),
+            Some(Location::new(module.unit(), header.span())),
+        ));
+    }
 
-        for decorator in scope.pop_decorators() {
-            match decorator {
-                dst::decorator::Application {
-                    decorator: dst::decorator::Builtin::Builtin,
-                    ..
-                } => {
-                    if builtin.is_some() {
-                        return Err(Panic::new(
-                            "Duplicate decorator `Builtin`".to_string(),
-                            Some(Location::new(scope.unit(), self.id.span())),
-                        ));
-                    }
+    if !header.value.starts_with("./") && !header.value.starts_with("../") {
+        return Ok(header.value.clone());
+    }
 
-                    match self.id.value.as_str() {
-                        "Bool" => {
-                            builtin = Some(dst::r#struct::Builtin::Bool);
-                        }
-                        &_ => {
-                            return Err(Panic::new(
-                                format!("Unknown builtin struct {}", &self.id),
-                                Some(Location::new(scope.unit(), self.id.span())),
-                            ))
-                        }
-                    }
-                }
+    let mut path = module.path();
+    path.pop();
+    path.push(&header.value);
+
+    match path.canonicalize() {
+        Ok(path) => Ok(path.to_string_lossy().to_string()),
+        Err(err) => Err(Panic::new(
+            format!("Failed to read C header at \"{}\": {}", path.display(), err),
+            Some(Location::new(module.unit(), header.span())),
+        )),
+    }
+}
+
+/// Resolve a declaration statement, which may appear
+/// either at the module level or within a namespace.
+/// Declare a struct, error or namespace, recursively,
+/// so that it may be referenced before its declaration.
+/// Other statements are left to the later passes.
+fn declare_types(stmt: &ast::Statement, scope: &mut dyn dst::Scope) -> Result<(), Panic> {
+    match stmt {
+        ast::Statement::Decorator(d) => {
+            let decorator = d.resolve(scope)?;
+            scope.push_decorator(decorator);
+        }
+        ast::Statement::StructDef(def) => {
+            let decl = def.resolve(scope)?;
+            scope.store(dst::Exportable::StructDecl(Rc::clone(&decl)))?;
+
+            if def.r#pub {
+                scope.export(dst::Exportable::StructDecl(decl));
             }
         }
+        ast::Statement::ErrorDecl(decl) => {
+            let dst = decl.resolve(scope)?;
+            scope.store(dst::Exportable::ErrorDecl(Rc::clone(&dst)))?;
 
-        if builtin.is_none() {
-            unimplemented!("Non-builtin struct");
+            if decl.r#pub {
+                scope.export(dst::Exportable::ErrorDecl(dst));
+            }
         }
+        ast::Statement::Namespace(namespace) => {
+            let dst = namespace.resolve(scope)?;
+            scope.store(dst::Exportable::Namespace(Rc::clone(&dst)))?;
 
-        let decl = Rc::new(RefCell::new(dst::r#struct::Decl::new(
-            self.clone(),
-            builtin,
-        )));
+            if namespace.r#pub {
+                scope.export(dst::Exportable::Namespace(Rc::clone(&dst)));
+            }
 
-        let r#impl = Rc::new(dst::r#struct::Impl::new(Rc::downgrade(&decl)));
-        decl.borrow_mut().add_impl(r#impl);
+            let mut namespace_scope = dst::NamespaceScope::new(scope, dst);
 
-        Ok(decl)
+            for el in &namespace.body {
+                match el {
+                    ast::BlockBody::Stmt(
+                        stmt @ (ast::Statement::Decorator(_)
+                        | ast::Statement::StructDef(_)
+                        | ast::Statement::StructImpl(_)
+                        | ast::Statement::FunctionDecl(_)
+                        | ast::Statement::ErrorDecl(_)
+                        | ast::Statement::Namespace(_)),
+                    ) => declare_types(stmt, &mut namespace_scope)?,
+                    ast::BlockBody::Comment(_) => {
+                        // Do nothing.
+                    }
+                    _ => {
+                        return Err(Panic::new(
+                            "Only declarations are allowed within a namespace".to_string(),
+                            Some(Location::new(namespace_scope.unit(), el.span())),
+                        ))
+                    }
+                }
+            }
+        }
+        _ => {
+            // Decorators of functions are applied when declaring functions.
+            scope.pop_decorators();
+        }
     }
+
+    Ok(())
 }
 
-impl Resolve<Rc<RefCell<dst::function::Decl>>> for ast::function::Decl {
-    fn resolve(
-        &self,
-        scope: &mut dyn dst::Scope,
-    ) -> Result<Rc<RefCell<dst::function::Decl>>, Panic> {
-        let mut builtin: Option<dst::function::Builtin> = None;
+/// Declare function signatures, including struct functions,
+/// once all types are declared. Pushes declared functions to `pending`,
+/// so that their bodies are resolved by `define_functions`.
+fn declare_functions(
+    stmt: &ast::Statement,
+    scope: &mut dyn dst::Scope,
+    pending: &mut Vec<Rc<RefCell<dst::function::Decl>>>,
+) -> Result<(), Panic> {
+    match stmt {
+        ast::Statement::Decorator(d) => {
+            let decorator = d.resolve(scope)?;
+            scope.push_decorator(decorator);
+        }
+        ast::Statement::StructDef(def) => {
+            // Struct decorators are already applied.
+            scope.pop_decorators();
 
-        for decorator in scope.pop_decorators() {
-            match decorator {
-                dst::decorator::Application {
-                    decorator: dst::decorator::Builtin::Builtin,
-                    ..
-                } => {
-                    if builtin.is_some() {
-                        return Err(Panic::new(
-                            "Duplicate decorator `Builtin`".to_string(),
-                            Some(Location::new(scope.unit(), self.id.span())),
-                        ));
-                    }
+            let decl = search_declared_struct(&def.id, scope);
+            let functions = resolve_struct_body(&def.body, &decl, scope)?;
+            pending.extend(functions.iter().cloned());
 
-                    match self.id.id.value.as_str() {
-                        "eq?" => {
-                            builtin = Some(dst::function::Builtin::BoolEq);
-                        }
-                        &_ => {
-                            return Err(Panic::new(
-                                format!("Unknown builtin function {}", &self.id),
-                                Some(Location::new(scope.unit(), self.id.span())),
-                            ))
-                        }
-                    }
+            let r#impl = Rc::new(dst::r#struct::Impl::new(
+                Rc::downgrade(&decl),
+                Rc::downgrade(&scope.unit()),
+                functions,
+            ));
+
+            decl.borrow_mut().add_impl(Rc::clone(&r#impl));
+            scope.store_impl(r#impl);
+        }
+        ast::Statement::StructImpl(i) => {
+            let r#impl = i.resolve(scope)?;
+            pending.extend(r#impl.functions.iter().cloned());
+            scope.store_impl(r#impl);
+        }
+        ast::Statement::FunctionDecl(decl) => {
+            let dst = decl.resolve(scope)?;
+            pending.push(Rc::clone(&dst));
+
+            if decl.shorthand.is_some() {
+                // A freestanding `::foo(self: T)` is attached to `T`.
+                let r#impl = attach_to_receiver(decl, dst, scope)?;
+                scope.store_impl(r#impl);
+                return Ok(());
+            }
+
+            scope.store(dst::Exportable::FunctionDecl(Rc::clone(&dst)))?;
+
+            if decl.r#pub {
+                scope.export(dst::Exportable::FunctionDecl(dst));
+            }
+        }
+        ast::Statement::Namespace(namespace) => {
+            let dst = search_declared_namespace(&namespace.id, scope);
+            let mut namespace_scope = dst::NamespaceScope::new(scope, dst);
+
+            for el in &namespace.body {
+                if let ast::BlockBody::Stmt(stmt) = el {
+                    declare_functions(stmt, &mut namespace_scope, pending)?;
                 }
             }
         }
+        _ => {
+            // Decorators of types are already applied.
+            scope.pop_decorators();
+        }
+    }
+
+    Ok(())
+}
 
-        if builtin.is_none() {
-            unimplemented!("Non-builtin function");
+/// Resolve bodies of the functions declared by `stmt`,
+/// taking them from `pending`.
+fn define_functions(
+    stmt: &ast::Statement,
+    scope: &mut dyn dst::Scope,
+    pending: &mut Vec<Rc<RefCell<dst::function::Decl>>>,
+) -> Result<(), Panic> {
+    match stmt {
+        ast::Statement::StructDef(def) => {
+            let decl = search_declared_struct(&def.id, scope);
+            define_struct_functions(&def.body, decl, scope, pending)?;
+        }
+        ast::Statement::StructImpl(i) => {
+            let decl: Rc<RefCell<dst::r#struct::Decl>> = i.r#type.resolve(scope)?;
+            define_struct_functions(&i.body, decl, scope, pending)?;
         }
+        ast::Statement::FunctionDecl(decl) => {
+            let dst = take_pending(decl, pending);
+            resolve_function_body(decl, &dst, scope)?;
+        }
+        ast::Statement::Namespace(namespace) => {
+            let dst = search_declared_namespace(&namespace.id, scope);
+            let mut namespace_scope = dst::NamespaceScope::new(scope, dst);
 
-        let mut params: Vec<dst::function::decl::Param> = vec![];
+            for el in &namespace.body {
+                if let ast::BlockBody::Stmt(stmt) = el {
+                    define_functions(stmt, &mut namespace_scope, pending)?;
+                }
+            }
+        }
+        _ => {}
+    }
 
-        for param in &self.params {
-            let param =
-                dst::function::decl::Param::new(param.id.clone(), param.r#type.resolve(scope)?);
-            params.push(param);
+    Ok(())
+}
+
+/// Resolve bodies of functions declared in a struct body.
+fn define_struct_functions(
+    body: &[ast::BlockBody],
+    decl: Rc<RefCell<dst::r#struct::Decl>>,
+    scope: &mut dyn dst::Scope,
+    pending: &mut Vec<Rc<RefCell<dst::function::Decl>>>,
+) -> Result<(), Panic> {
+    let mut struct_scope = dst::StructScope::new(scope, decl);
+
+    for el in body {
+        if let ast::BlockBody::Stmt(ast::Statement::FunctionDecl(f)) = el {
+            let dst = take_pending(f, pending);
+            resolve_function_body(f, &dst, &mut struct_scope)?;
         }
+    }
 
-        let return_type = self.return_type.resolve(scope)?;
+    Ok(())
+}
 
-        let decl = Rc::new(RefCell::new(dst::function::Decl::new(
-            self.clone(),
-            builtin,
-            params,
-            Some(return_type),
-        )));
+/// Take the function declared from `ast_node` out of `pending`.
+fn take_pending(
+    ast_node: &ast::function::Decl,
+    pending: &mut Vec<Rc<RefCell<dst::function::Decl>>>,
+) -> Rc<RefCell<dst::function::Decl>> {
+    let index = pending
+        .iter()
+        .position(|f| f.borrow().span() == ast_node.span())
+        .expect("Function must be declared");
 
-        Ok(decl)
+    pending.swap_remove(index)
+}
+
+/// Return a struct stored in the scope by `declare_types`.
+fn search_declared_struct(
+    id: &ast::Id,
+    scope: &dyn dst::Scope,
+) -> Rc<RefCell<dst::r#struct::Decl>> {
+    match scope.search(id) {
+        Some(dst::Exportable::StructDecl(decl)) => decl,
+        _ => unreachable!("Struct {} must be declared", id),
     }
 }
 
-impl Resolve<Rc<dst::Expr>> for ast::Expr {
-    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<Rc<dst::Expr>, Panic> {
-        match self {
-            ast::Expr::BoolLiteral(b) => Ok(Rc::new(dst::Expr::BoolLiteral(b.clone()))),
-            ast::Expr::Ref(id) => {
-                if let Some(ent) = scope.search(&id.id) {
-                    match ent {
-                        dst::Exportable::VarDecl(var) => Ok(Rc::new(dst::Expr::VarRef(
-                            dst::VarRef::new(id.id.clone(), Rc::clone(&var)),
-                        ))),
-                        dst::Exportable::StructDecl(_) => Err(Panic::new(
-                            format!("Cannot use struct {} as a value", id),
-                            Some(Location::new(scope.unit(), id.span())),
-                        )),
-                        dst::Exportable::FunctionDecl(_) => Err(Panic::new(
-                            format!("Cannot use function {} as a value", id),
-                            Some(Location::new(scope.unit(), id.span())),
-                        )),
+/// Return a namespace stored in the scope by `declare_types`.
+fn search_declared_namespace(id: &ast::Id, scope: &dyn dst::Scope) -> Rc<RefCell<dst::Namespace>> {
+    match scope.search(id) {
+        Some(dst::Exportable::Namespace(namespace)) => namespace,
+        _ => unreachable!("Namespace {} must be declared", id),
+    }
+}
+
+/// Resolve a function body.
+fn resolve_block(
+    body: &[ast::BlockBody],
+    scope: &mut dyn dst::Scope,
+) -> Result<Vec<dst::Statement>, Panic> {
+    let mut statements = Vec::new();
+
+    for (i, el) in body.iter().enumerate() {
+        if !matches!(el, ast::BlockBody::Comment(_)) {
+            ensure_reachable(&statements, el.span(), scope)?;
+        }
+
+        match el {
+            ast::BlockBody::Stmt(ast::Statement::If(r#if)) => {
+                let (r#if, narrowed) = resolve_if(r#if, scope)?;
+                statements.push(dst::Statement::If(Box::new(r#if)));
+
+                // The rest of the block is only reached if the narrowing holds,
+                // e.g. after `if x == nil { return }`.
+                if !narrowed.is_empty() {
+                    let rest = &body[i + 1..];
+
+                    if let Some(el) = rest
+                        .iter()
+                        .find(|el| !matches!(el, ast::BlockBody::Comment(_)))
+                    {
+                        ensure_reachable(&statements, el.span(), scope)?;
                     }
-                } else {
-                    Err(Panic::new(
-                        format!("Invalid reference {}", id),
-                        Some(Location::new(scope.unit(), id.span())),
-                    ))
+
+                    let mut block_scope = dst::BlockScope::new(scope, narrowed);
+                    statements.extend(resolve_block(rest, &mut block_scope)?);
+                    break;
                 }
             }
-            ast::Expr::MacroCall(m) => Ok(Rc::new(dst::Expr::MacroCall(m.resolve(scope)?))),
-            ast::Expr::Binop(b) => match b.op.as_str() {
-                "=" => {
-                    let lhs = b.lhs.resolve(scope)?;
-                    let rhs = b.rhs.resolve(scope)?;
+            ast::BlockBody::Stmt(ast::Statement::VarDecl(var_decl)) => {
+                let var = var_decl.resolve(scope)?;
+                scope.store(dst::Exportable::VarDecl(Rc::clone(&var)))?;
+                statements.push(dst::Statement::VarDecl(var));
+            }
+            ast::BlockBody::Stmt(ast::Statement::TerminatedExpr(expr)) => {
+                let expr = expr.resolve(scope)?;
+                statements.push(dst::Statement::TerminatedExpr(expr));
+            }
+            ast::BlockBody::Stmt(ast::Statement::Return(r)) => {
+                let function = scope.enclosing_function_type().ok_or_else(|| {
+                    Panic::new(
+                        "Cannot return outside of a function".to_string(),
+                        Some(Location::new(scope.unit(), r.span())),
+                    )
+                })?;
 
-                    if let dst::Expr::VarRef(r#ref) = &*lhs {
-                        let rhs_type = rhs.infer_type(scope);
+                let expected = function.return_type.clone();
 
-                        if rhs_type.is_none() {
-                            return Err(Panic::new(
-                                "Expression result must not be void".to_string(),
-                                Some(Location::new(scope.unit(), b.span())),
-                            ));
-                        }
+                let expr = match &r.expr {
+                    Some(expr) => Some(coerce_c_string(expr.resolve(scope)?, Some(&expected))),
+                    None => None,
+                };
 
-                        if r#ref.decl.r#type != *rhs_type.as_ref().unwrap() {
-                            return Err(Panic::new(
-                                format!(
-                                    "Type mismatch: left is {}, right is {}",
-                                    r#ref.decl.r#type.as_ref().borrow(),
-                                    rhs_type.unwrap().as_ref().borrow()
-                                ),
-                                Some(Location::new(scope.unit(), rhs.span())),
-                            ));
-                        }
+                let actual = expr
+                    .as_ref()
+                    .map_or(dst::Type::Void, |e| e.infer_type(scope));
 
-                        Ok(Rc::new(dst::Expr::Assignment(dst::Assignment {
-                            lhs: r#ref.clone(),
-                            rhs,
-                        })))
-                    } else {
-                        Err(Panic::new(
-                            "Left-hand side of assignment must be a variable".to_string(),
-                            Some(Location::new(scope.unit(), lhs.span())),
-                        ))
-                    }
+                if !expected.accepts(&actual) {
+                    return Err(Panic::new(
+                        format!(
+                            "Type mismatch: expected {}, returned {}",
+                            expected,
+                            expr.as_ref()
+                                .map_or(actual.to_string(), |e| display_type(e, scope))
+                        ),
+                        Some(Location::new(scope.unit(), r.span())),
+                    ));
                 }
-                &_ => todo!(),
-            },
-            ast::Expr::FunctionCall(call) => {
-                let callee: Rc<RefCell<dst::function::Decl>> = call.callee.resolve(scope)?;
-                let mut args: Vec<Rc<dst::Expr>> = vec![];
 
-                for arg in &call.args {
-                    args.push(arg.resolve(scope)?);
+                if let Some(expr) = &expr {
+                    ensure_not_escaping(expr, scope)?;
+                }
+
+                statements.push(dst::Statement::Return(expr));
+            }
+            ast::BlockBody::Stmt(ast::Statement::Throw(t)) => {
+                let function = scope.enclosing_function_type().ok_or_else(|| {
+                    Panic::new(
+                        "Cannot throw outside of a function".to_string(),
+                        Some(Location::new(scope.unit(), t.span())),
+                    )
+                })?;
+
+                let error = resolve_error(&t.error, scope)?;
+
+                if !function.throws.contains(&error) {
+                    let mut panic = Panic::new(
+                        format!("Cannot throw undeclared error {}", t.error),
+                        Some(Location::new(scope.unit(), t.span())),
+                    );
+
+                    panic.add_note(
+                        format!("Add {} to the function `throws` clause", t.error),
+                        None,
+                    );
+
+                    return Err(panic);
+                }
+
+                statements.push(dst::Statement::Throw(error));
+            }
+            ast::BlockBody::Expr(expr) => {
+                let expr = expr.resolve(scope)?;
+
+                let r#type = expr.infer_type(scope);
+
+                if !r#type.is_void() && !r#type.is_never() {
+                    return Err(Panic::new(
+                        "Unused expression result".to_string(),
+                        Some(Location::new(scope.unit(), expr.span())),
+                    ));
                 }
 
-                let dstn = dst::Call::new(call.clone(), callee, args);
-                Ok(Rc::new(dst::Expr::FunctionCall(dstn)))
+                statements.push(dst::Statement::TerminatedExpr(expr));
+            }
+            ast::BlockBody::Comment(_) => {
+                // Do nothing.
+            }
+            ast::BlockBody::Stmt(stmt) => {
+                return Err(Panic::new(
+                    "Only variable declarations and expressions are allowed within a function"
+                        .to_string(),
+                    Some(Location::new(scope.unit(), stmt.span())),
+                ))
             }
         }
     }
+
+    Ok(statements)
 }
 
-impl Resolve<Rc<dst::VarDecl>> for ast::VarDecl {
-    /// Pushes the resolved variable declaration to the scope.
-    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<Rc<dst::VarDecl>, Panic> {
-        // TODO: Apply decorators.
-        let expr = self.expr.resolve(scope)?;
-        let r#type = expr.infer_type(scope);
+/// Resolve an `if` statement, narrowing optional variables in its bodies.
+/// Also return the narrowings holding after the statement,
+/// i.e. when only one of the bodies continues execution.
+fn resolve_if(
+    r#if: &ast::If,
+    scope: &mut dyn dst::Scope,
+) -> Result<(dst::If, Vec<(dst::Exportable, dst::Type)>), Panic> {
+    let mut then_narrowed = Vec::new();
+    let mut else_narrowed = Vec::new();
 
-        if r#type.is_none() {
-            return Err(Panic::new(
-                "Expression returns void".to_string(),
-                Some(Location::new(scope.unit(), expr.span())),
-            ));
-        }
+    let condition = match &r#if.condition {
+        ast::Condition::Expr(expr) => {
+            let condition = expr.resolve(scope)?;
+            let r#type = condition.infer_type(scope);
 
-        let var = Rc::new(dst::VarDecl::new(self.clone(), r#type.unwrap(), expr));
-        Ok(var)
-    }
-}
+            if !r#type.is_bool() {
+                return Err(Panic::new(
+                    format!("Condition must be `Bool`, got {}", r#type),
+                    Some(Location::new(scope.unit(), condition.span())),
+                ));
+            }
 
-impl Resolve<dst::MacroCall> for ast::MacroCall {
-    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<dst::MacroCall, Panic> {
-        match self.id.value.as_str() {
-            "assert" => {
-                assert_eq!(self.args.len(), 1);
-                let arg = &self.args[0].resolve(scope)?;
-                Ok(dst::MacroCall::Assert(self.clone(), Rc::clone(arg)))
+            // Only values which no call may change are narrowed, i.e. not
+            // module-level variables; others must be copied with `if let`.
+            if let dst::Expr::NilCheck(check) = condition.as_ref() {
+                let target = match check.value.as_ref() {
+                    dst::Expr::VarRef(r)
+                        if !r.decl.is_global() && !r.decl.captured_by_ref.get() =>
+                    {
+                        Some(dst::Exportable::VarDecl(Rc::clone(&r.decl)))
+                    }
+                    dst::Expr::ParamRef(r) => Some(dst::Exportable::Param(Rc::clone(&r.param))),
+                    dst::Expr::CaptureRef(r) if !r.capture.by_ref => {
+                        Some(dst::Exportable::Capture(Rc::clone(&r.capture)))
+                    }
+                    _ => None,
+                };
+
+                if let Some(target) = target {
+                    let inner = check.value.infer_type(scope).as_optional().unwrap().clone();
+
+                    if check.is_nil {
+                        else_narrowed.push((target, inner));
+                    } else {
+                        then_narrowed.push((target, inner));
+                    }
+                }
             }
-            _ => Err(Panic::new(
-                format!("Unknown macro: {}", self.id.value),
-                Some(Location::new(scope.unit(), self.id.span())),
-            )),
+
+            dst::Condition::Expr(condition)
         }
-    }
+        ast::Condition::Let(id, expr) => {
+            let value = expr.resolve(scope)?;
+
+            let inner = match value.infer_type(scope) {
+                dst::Type::Optional(inner) if !inner.is_never() => *inner,
+                _ => {
+                    return Err(Panic::new(
+                        format!(
+                            "Cannot unwrap {}, as it is not optional",
+                            display_type(&value, scope)
+                        ),
+                        Some(Location::new(scope.unit(), value.span())),
+                    ))
+                }
+            };
+
+            let ast_node =
+                ast::VarDecl::new(id.span().join(expr.span()), false, id.clone(), expr.clone());
+            dst::Condition::Let(Rc::new(dst::VarDecl::new(ast_node, inner, value)))
+        }
+    };
+
+    let then = {
+        let mut then_scope = dst::BlockScope::new(scope, then_narrowed.clone());
+
+        if let dst::Condition::Let(var) = &condition {
+            then_scope.store(dst::Exportable::VarDecl(Rc::clone(var)))?;
+        }
+
+        resolve_block(&r#if.then, &mut then_scope)?
+    };
+
+    let r#else = match &r#if.r#else {
+        Some(body) => {
+            let mut else_scope = dst::BlockScope::new(scope, else_narrowed.clone());
+            Some(resolve_block(body, &mut else_scope)?)
+        }
+        None => None,
+    };
+
+    let diverges = |body: &[dst::Statement]| body.last().is_some_and(|s| s.diverges());
+    let else_diverges = r#else.as_deref().is_some_and(diverges);
+
+    let narrowed = match (diverges(&then), else_diverges) {
+        (true, false) => else_narrowed,
+        (false, true) => then_narrowed,
+        _ => Vec::new(),
+    };
+
+    Ok((
+        dst::If::new(r#if.clone(), condition, then, r#else),
+        narrowed,
+    ))
+}
+
+/// Ensure a statement at `span` follows no diverging statement.
+fn ensure_reachable(
+    statements: &[dst::Statement],
+    span: Span,
+    scope: &dyn dst::Scope,
+) -> Result<(), Panic> {
+    if statements.last().is_some_and(|s| s.diverges()) {
+        let mut panic = Panic::new(
+            "Unreachable code".to_string(),
+            Some(Location::new(scope.unit(), span)),
+        );
+
+        panic.add_note(
+            "The preceding statement never continues execution".to_string(),
+            None,
+        );
+
+        return Err(panic);
+    }
+
+    Ok(())
+}
+
+/// Resolve a struct or impl body into a list of functions attached to `decl`.
+fn resolve_struct_body(
+    body: &[ast::BlockBody],
+    decl: &Rc<RefCell<dst::r#struct::Decl>>,
+    scope: &mut dyn dst::Scope,
+) -> Result<Vec<Rc<RefCell<dst::function::Decl>>>, Panic> {
+    let mut functions: Vec<Rc<RefCell<dst::function::Decl>>> = vec![];
+    let mut struct_scope = dst::StructScope::new(scope, Rc::clone(decl));
+
+    for el in body {
+        match el {
+            ast::BlockBody::Stmt(ast::Statement::Decorator(d)) => {
+                let decorator = d.resolve(&mut struct_scope)?;
+                struct_scope.push_decorator(decorator);
+            }
+            ast::BlockBody::Stmt(ast::Statement::FunctionDecl(f)) => {
+                if decl.borrow().is_generic() {
+                    return Err(Panic::new(
+                        format!("Generic {} may not declare functions yet", decl.borrow()),
+                        Some(Location::new(struct_scope.unit(), f.id.span())),
+                    ));
+                }
+
+                if let Some(found) = functions.iter().find(|x| x.borrow().id() == f.id.id) {
+                    let mut panic = Panic::new(
+                        format!("{} already declared", f.id),
+                        Some(Location::new(struct_scope.unit(), f.id.span())),
+                    );
+
+                    panic.add_note(
+                        "Previously declared here".to_string(),
+                        Some(Location::new(
+                            struct_scope.unit(),
+                            found.borrow().id().span(),
+                        )),
+                    );
+
+                    return Err(panic);
+                }
+
+                ensure_function_not_found(decl, &f.id.id, &struct_scope)?;
+                functions.push(f.resolve(&mut struct_scope)?);
+            }
+            ast::BlockBody::Comment(_) => {
+                // Do nothing.
+            }
+            _ => {
+                return Err(Panic::new(
+                    "Only function declarations are allowed within a struct".to_string(),
+                    Some(Location::new(struct_scope.unit(), el.span())),
+                ))
+            }
+        }
+    }
+
+    Ok(functions)
+}
+
+/// Attach a freestanding shorthand function declaration
+/// to the type of its `self` parameter.
+fn attach_to_receiver(
+    ast_node: &ast::function::Decl,
+    decl: Rc<RefCell<dst::function::Decl>>,
+    scope: &dyn dst::Scope,
+) -> Result<Rc<dst::r#struct::Impl>, Panic> {
+    if !decl.borrow().is_instance() {
+        return Err(Panic::new(
+            format!(
+                "Freestanding function {} requires a `self` parameter",
+                ast_node.id
+            ),
+            Some(Location::new(scope.unit(), ast_node.id.span())),
+        ));
+    }
+
+    let receiver = match &decl.borrow().params[0].r#type {
+        dst::Type::Struct(receiver) => Rc::clone(receiver),
+        r#type => {
+            return Err(Panic::new(
+                format!("Cannot attach function {} to {}", ast_node.id, r#type),
+                Some(Location::new(scope.unit(), ast_node.params[0].span)),
+            ))
+        }
+    };
+    ensure_function_not_found(&receiver, &ast_node.id.id, scope)?;
+    decl.borrow_mut().owner = Some(Rc::downgrade(&receiver));
+
+    let r#impl = Rc::new(dst::r#struct::Impl::new(
+        Rc::downgrade(&receiver),
+        Rc::downgrade(&scope.unit()),
+        vec![decl],
+    ));
+
+    receiver.borrow_mut().add_impl(Rc::clone(&r#impl));
+
+    Ok(r#impl)
+}
+
+/// Onyx-panic if `id` is already declared for the struct
+/// in any of its impls visible from the scope.
+fn ensure_function_not_found(
+    r#struct: &Rc<RefCell<dst::r#struct::Decl>>,
+    id: &ast::Id,
+    scope: &dyn dst::Scope,
+) -> Result<(), Panic> {
+    let found = r#struct.borrow().search_functions(id, &scope.unit());
+
+    if let Some(found) = found.first() {
+        let mut panic = Panic::new(
+            format!("{} already declared for {}", id, r#struct.borrow().id()),
+            Some(Location::new(scope.unit(), id.span())),
+        );
+
+        panic.add_note(
+            "Previously declared here".to_string(),
+            Some(Location::new(
+                found.borrow().unit.upgrade().unwrap(),
+                found.borrow().id().span(),
+            )),
+        );
+
+        return Err(panic);
+    }
+
+    Ok(())
+}
+
+/// Onyx-panic if `expr` is a closure referring to local variables,
+/// which is returned, assigned or passed, and thus may outlive them.
+fn ensure_not_escaping(expr: &dst::Expr, scope: &dyn dst::Scope) -> Result<(), Panic> {
+    if !expr.borrows_locals() {
+        return Ok(());
+    }
+
+    let mut panic = Panic::new(
+        "Closure capturing local variables by reference may outlive them".to_string(),
+        Some(Location::new(scope.unit(), expr.span())),
+    );
+
+    panic.add_note(
+        "Capture by value, or only call the closure where it's declared".to_string(),
+        None,
+    );
+
+    Err(panic)
+}
+
+/// Onyx-panic if `r#type` may not cross the C boundary.
+fn ensure_ffi_safe(r#type: &dst::Type, span: Span, scope: &dyn dst::Scope) -> Result<(), Panic> {
+    if r#type.is_ffi_safe() {
+        return Ok(());
+    }
+
+    let mut panic = Panic::new(
+        format!("{} is not FFI-safe", r#type),
+        Some(Location::new(scope.unit(), span)),
+    );
+
+    panic.add_note(
+        "Only `Bool`, `CInt`, `CString`, `@[Extern]` structs and pointers to them \
+            may cross the C boundary"
+            .to_string(),
+        None,
+    );
+
+    Err(panic)
+}
+
+/// Whether `name` is a valid C identifier.
+fn is_c_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+impl Resolve<dst::decorator::Application> for ast::Decorator {
+    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<dst::decorator::Application, Panic> {
+        let expect_args = |count: usize, usage: &str| {
+            if self.args.len() == count {
+                Ok(())
+            } else {
+                let mut panic = Panic::new(
+                    format!(
+                        "Decorator {} expects {} arguments, got {}",
+                        self.id,
+                        count,
+                        self.args.len()
+                    ),
+                    Some(Location::new(scope.unit(), self.span())),
+                );
+
+                panic.add_note(format!("Usage: `{}`", usage), None);
+                Err(panic)
+            }
+        };
+
+        let decorator = match self.id.value.as_str() {
+            "Builtin" => {
+                expect_args(0, "@[Builtin]")?;
+                dst::decorator::Builtin::Builtin
+            }
+            "Extern" => {
+                expect_args(0, "@[Extern]")?;
+                dst::decorator::Builtin::Extern
+            }
+            "Export" => {
+                expect_args(1, "@[Export(\"c_name\")]")?;
+                let name = &self.args[0];
+
+                if !is_c_identifier(&name.value) {
+                    return Err(Panic::new(
+                        format!("{} is not a valid C identifier", name),
+                        Some(Location::new(scope.unit(), name.span())),
+                    ));
+                }
+
+                dst::decorator::Builtin::Export(name.value.clone())
+            }
+
+            // TODO: Lookup for the decorator definition in the scope.
+            _ => {
+                return Err(Panic::new(
+                    format!("Unknown decorator {}", &self.id),
+                    Some(Location::new(scope.unit(), self.id.span())),
+                ))
+            }
+        };
+
+        Ok(dst::decorator::Application::new(self.clone(), decorator))
+    }
+}
+
+impl Resolve<Rc<RefCell<dst::r#struct::Decl>>> for ast::r#struct::Def {
+    /// Resolves the struct itself; its functions are declared later.
+    fn resolve(
+        &self,
+        scope: &mut dyn dst::Scope,
+    ) -> Result<Rc<RefCell<dst::r#struct::Decl>>, Panic> {
+        let mut builtin: Option<dst::r#struct::Builtin> = None;
+        let mut r#extern = false;
+
+        for decorator in scope.pop_decorators() {
+            match decorator {
+                dst::decorator::Application {
+                    decorator: dst::decorator::Builtin::Extern,
+                    ..
+                } => {
+                    if r#extern {
+                        return Err(Panic::new(
+                            "Duplicate decorator `Extern`".to_string(),
+                            Some(Location::new(scope.unit(), self.id.span())),
+                        ));
+                    }
+
+                    if !is_c_identifier(&self.id.value) {
+                        return Err(Panic::new(
+                            format!(
+                                "Extern struct {} must be named a valid C identifier",
+                                self.id
+                            ),
+                            Some(Location::new(scope.unit(), self.id.span())),
+                        ));
+                    }
+
+                    r#extern = true;
+                }
+                dst::decorator::Application {
+                    ast_node,
+                    decorator: dst::decorator::Builtin::Export(_),
+                } => {
+                    return Err(Panic::new(
+                        format!("Decorator {} is not applicable to a struct", ast_node),
+                        Some(Location::new(scope.unit(), ast_node.span())),
+                    ))
+                }
+                dst::decorator::Application {
+                    decorator: dst::decorator::Builtin::Builtin,
+                    ..
+                } => {
+                    if builtin.is_some() {
+                        return Err(Panic::new(
+                            "Duplicate decorator `Builtin`".to_string(),
+                            Some(Location::new(scope.unit(), self.id.span())),
+                        ));
+                    }
+
+                    match self.id.value.as_str() {
+                        "Bool" => {
+                            builtin = Some(dst::r#struct::Builtin::Bool);
+                        }
+                        "CInt" => {
+                            builtin = Some(dst::r#struct::Builtin::CInt);
+                        }
+                        "CString" => {
+                            builtin = Some(dst::r#struct::Builtin::CString);
+                        }
+                        "String" => {
+                            builtin = Some(dst::r#struct::Builtin::String);
+                        }
+                        &_ => {
+                            return Err(Panic::new(
+                                format!("Unknown builtin struct {}", &self.id),
+                                Some(Location::new(scope.unit(), self.id.span())),
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+
+        if !self.type_params.is_empty() && (builtin.is_some() || r#extern) {
+            return Err(Panic::new(
+                format!("Builtin or extern struct {} must not be generic", self.id),
+                Some(Location::new(scope.unit(), self.id.span())),
+            ));
+        }
+
+        for (i, param) in self.type_params.iter().enumerate() {
+            if let Some(found) = self.type_params[..i]
+                .iter()
+                .find(|p| p.value == param.value)
+            {
+                let mut panic = Panic::new(
+                    format!("Type parameter {} already declared", param),
+                    Some(Location::new(scope.unit(), param.span())),
+                );
+
+                panic.add_note(
+                    "Previously declared here".to_string(),
+                    Some(Location::new(scope.unit(), found.span())),
+                );
+
+                return Err(panic);
+            }
+        }
+
+        let decl = Rc::new(RefCell::new(dst::r#struct::Decl::new(
+            self.clone(),
+            Rc::downgrade(&scope.unit()),
+            scope.namespace(),
+            builtin,
+            r#extern,
+        )));
+
+        Ok(decl)
+    }
+}
+
+impl Resolve<Rc<dst::r#struct::Impl>> for ast::r#struct::Impl {
+    /// Resolves the function signatures only.
+    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<Rc<dst::r#struct::Impl>, Panic> {
+        if let Some(decorator) = scope.pop_decorators().first() {
+            return Err(Panic::new(
+                format!(
+                    "Decorator {} is not applicable to an impl",
+                    decorator.ast_node
+                ),
+                Some(Location::new(scope.unit(), decorator.ast_node.span())),
+            ));
+        }
+
+        let decl: Rc<RefCell<dst::r#struct::Decl>> = self.r#type.resolve(scope)?;
+        let functions = resolve_struct_body(&self.body, &decl, scope)?;
+
+        let r#impl = Rc::new(dst::r#struct::Impl::new(
+            Rc::downgrade(&decl),
+            Rc::downgrade(&scope.unit()),
+            functions,
+        ));
+
+        decl.borrow_mut().add_impl(Rc::clone(&r#impl));
+
+        Ok(r#impl)
+    }
+}
+
+impl Resolve<Rc<RefCell<dst::Namespace>>> for ast::Namespace {
+    /// Resolves the namespace itself; its body is declared by the caller.
+    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<Rc<RefCell<dst::Namespace>>, Panic> {
+        if let Some(decorator) = scope.pop_decorators().first() {
+            return Err(Panic::new(
+                format!(
+                    "Decorator {} is not applicable to a namespace",
+                    decorator.ast_node
+                ),
+                Some(Location::new(scope.unit(), decorator.ast_node.span())),
+            ));
+        }
+
+        Ok(Rc::new(RefCell::new(dst::Namespace::new(
+            self.clone(),
+            Rc::downgrade(&scope.unit()),
+            scope.namespace(),
+        ))))
+    }
+}
+
+impl Resolve<Rc<dst::error::Decl>> for ast::error::Decl {
+    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<Rc<dst::error::Decl>, Panic> {
+        if let Some(decorator) = scope.pop_decorators().first() {
+            return Err(Panic::new(
+                format!(
+                    "Decorator {} is not applicable to an error",
+                    decorator.ast_node
+                ),
+                Some(Location::new(scope.unit(), decorator.ast_node.span())),
+            ));
+        }
+
+        let namespace = scope.namespace();
+
+        let symbol = namespace
+            .iter()
+            .chain(std::iter::once(&self.id))
+            .fold(scope.unit().borrow().hash(), |symbol, id| {
+                format!("{}{}{}", symbol, MANGLE_SEPARATOR, id.value)
+            });
+
+        Ok(Rc::new(dst::error::Decl::new(
+            self.clone(),
+            Rc::downgrade(&scope.unit()),
+            namespace,
+            symbol,
+        )))
+    }
+}
+
+/// Look up an error declaration by its qualifier.
+fn resolve_error(
+    qualifier: &ast::Qualifier,
+    scope: &dyn dst::Scope,
+) -> Result<Rc<dst::error::Decl>, Panic> {
+    match qualifier.lookup(scope, &scope.unit())? {
+        dst::Exportable::ErrorDecl(decl) => Ok(decl),
+        _ => Err(Panic::new(
+            format!("{} is not an error", qualifier),
+            Some(Location::new(scope.unit(), qualifier.span())),
+        )),
+    }
+}
+
+impl Resolve<Rc<RefCell<dst::function::Decl>>> for ast::function::Decl {
+    /// Resolves the function signature; see `resolve_function_body`.
+    fn resolve(
+        &self,
+        scope: &mut dyn dst::Scope,
+    ) -> Result<Rc<RefCell<dst::function::Decl>>, Panic> {
+        let mut builtin: Option<dst::function::Builtin> = None;
+        let mut export: Option<String> = None;
+
+        for decorator in scope.pop_decorators() {
+            match decorator {
+                dst::decorator::Application {
+                    decorator: dst::decorator::Builtin::Export(name),
+                    ..
+                } => {
+                    if export.is_some() {
+                        return Err(Panic::new(
+                            "Duplicate decorator `Export`".to_string(),
+                            Some(Location::new(scope.unit(), self.id.span())),
+                        ));
+                    }
+
+                    export = Some(name);
+                }
+                dst::decorator::Application {
+                    ast_node,
+                    decorator: dst::decorator::Builtin::Extern,
+                } => {
+                    let mut panic = Panic::new(
+                        format!("Decorator {} is not applicable to a function", ast_node),
+                        Some(Location::new(scope.unit(), ast_node.span())),
+                    );
+
+                    panic.add_note(
+                        "Declare C functions with `extern fn` instead".to_string(),
+                        None,
+                    );
+
+                    return Err(panic);
+                }
+                dst::decorator::Application {
+                    decorator: dst::decorator::Builtin::Builtin,
+                    ..
+                } => {
+                    if builtin.is_some() {
+                        return Err(Panic::new(
+                            "Duplicate decorator `Builtin`".to_string(),
+                            Some(Location::new(scope.unit(), self.id.span())),
+                        ));
+                    }
+
+                    match self.id.id.value.as_str() {
+                        "eq?" => {
+                            builtin = Some(dst::function::Builtin::BoolEq);
+                        }
+                        &_ => {
+                            return Err(Panic::new(
+                                format!("Unknown builtin function {}", &self.id),
+                                Some(Location::new(scope.unit(), self.id.span())),
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+
+        if export.is_some() && (builtin.is_some() || self.r#extern || self.body.is_none()) {
+            return Err(Panic::new(
+                format!("Exported function {} must be defined in Onyx", self.id),
+                Some(Location::new(scope.unit(), self.id.span())),
+            ));
+        }
+
+        let r#extern = if self.r#extern {
+            if builtin.is_some() {
+                return Err(Panic::new(
+                    format!("Extern function {} must not be builtin", self.id),
+                    Some(Location::new(scope.unit(), self.id.span())),
+                ));
+            }
+
+            if self.shorthand.is_some() || scope.enclosing_struct().is_some() {
+                return Err(Panic::new(
+                    format!("Extern function {} must be freestanding", self.id),
+                    Some(Location::new(scope.unit(), self.id.span())),
+                ));
+            }
+
+            if self.body.is_some() {
+                return Err(Panic::new(
+                    format!("Extern function {} must not have a body", self.id),
+                    Some(Location::new(scope.unit(), self.id.span())),
+                ));
+            }
+
+            Some(dst::function::Extern {
+                variadic: self.variadic,
+            })
+        } else if self.variadic {
+            return Err(Panic::new(
+                format!("Function {} must be extern to be variadic", self.id),
+                Some(Location::new(scope.unit(), self.id.span())),
+            ));
+        } else {
+            None
+        };
+
+        if builtin.is_some() && self.body.is_some() {
+            return Err(Panic::new(
+                format!("Builtin function {} must not have a body", self.id),
+                Some(Location::new(scope.unit(), self.id.span())),
+            ));
+        } else if builtin.is_none() && r#extern.is_none() && self.body.is_none() {
+            return Err(Panic::new(
+                format!("Function {} must have a body", self.id),
+                Some(Location::new(scope.unit(), self.id.span())),
+            ));
+        }
+
+        let mut params: Vec<Rc<dst::function::decl::Param>> = vec![];
+
+        if self.shorthand == Some(ast::function::Shorthand::Instance) {
+            let self_type = scope.enclosing_struct().ok_or_else(|| {
+                Panic::new(
+                    format!("Instance function {} requires an enclosing struct", self.id),
+                    Some(Location::new(scope.unit(), self.id.span())),
+                )
+            })?;
+
+            if let Some(param) = self.params.iter().find(|p| p.id.value == "self") {
+                return Err(Panic::new(
+                    format!(
+                        "Instance function {} has an implicit `self` parameter",
+                        self.id
+                    ),
+                    Some(Location::new(scope.unit(), param.id.span())),
+                ));
+            }
+
+            let self_type = dst::Type::Struct(self_type);
+
+            if export.is_some() {
+                ensure_ffi_safe(&self_type, self.id.span(), scope)?;
+            }
+
+            params.push(Rc::new(dst::function::decl::Param::new(
+                ast::Id::new(self.id.span(), "self".to_string()),
+                self_type,
+            )));
+        }
+
+        for param in &self.params {
+            let r#type = param.r#type.resolve(scope)?;
+
+            if r#extern.is_some() || export.is_some() {
+                ensure_ffi_safe(&r#type, param.r#type.span(), scope)?;
+            }
+
+            params.push(Rc::new(dst::function::decl::Param::new(
+                param.id.clone(),
+                r#type,
+            )));
+        }
+
+        let return_type = match &self.return_type {
+            Some(return_type) => {
+                let r#type = return_type.resolve(scope)?;
+
+                if r#extern.is_some() || export.is_some() {
+                    ensure_ffi_safe(&r#type, return_type.span(), scope)?;
+                }
+
+                r#type
+            }
+            None => dst::Type::Void,
+        };
+
+        let mut throws: Vec<Rc<dst::error::Decl>> = vec![];
+
+        for qualifier in &self.throws {
+            if builtin.is_some() || r#extern.is_some() || export.is_some() {
+                return Err(Panic::new(
+                    format!(
+                        "Function {} must be a plain Onyx function to throw",
+                        self.id
+                    ),
+                    Some(Location::new(scope.unit(), qualifier.span())),
+                ));
+            }
+
+            let error = resolve_error(qualifier, scope)?;
+
+            if throws.contains(&error) {
+                return Err(Panic::new(
+                    format!("Duplicate thrown error {}", qualifier),
+                    Some(Location::new(scope.unit(), qualifier.span())),
+                ));
+            }
+
+            throws.push(error);
+        }
+
+        let decl = Rc::new(RefCell::new(dst::function::Decl::new(
+            self.clone(),
+            Rc::downgrade(&scope.unit()),
+            scope.namespace(),
+            builtin,
+            r#extern,
+            params,
+            return_type,
+        )));
+
+        decl.borrow_mut().owner = scope.enclosing_struct().map(|s| Rc::downgrade(&s));
+        decl.borrow_mut().export = export;
+        decl.borrow_mut().throws = throws;
+
+        Ok(decl)
+    }
+}
+
+/// Resolve the body of a function declared from `ast_node`, if any.
+fn resolve_function_body(
+    ast_node: &ast::function::Decl,
+    decl: &Rc<RefCell<dst::function::Decl>>,
+    scope: &mut dyn dst::Scope,
+) -> Result<(), Panic> {
+    if let Some(body) = &ast_node.body {
+        let mut function_scope = dst::FunctionScope::new(scope, Rc::clone(decl));
+        let body = resolve_block(body, &mut function_scope)?;
+
+        if !decl.borrow().return_type.is_void() && !body.last().is_some_and(|s| s.diverges()) {
+            return Err(Panic::new(
+                format!("Function {} must end with a `return`", ast_node.id),
+                Some(Location::new(scope.unit(), ast_node.id.span())),
+            ));
+        }
+
+        decl.borrow_mut().body = Some(body);
+    }
+
+    Ok(())
+}
+
+impl Resolve<dst::Type> for ast::TypeExpr {
+    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<dst::Type, Panic> {
+        match self {
+            ast::TypeExpr::Ref(qualifier) => {
+                let decl: Rc<RefCell<dst::r#struct::Decl>> = qualifier.resolve(scope)?;
+                ensure_type_args(&decl.borrow(), 0, self.span(), scope)?;
+                Ok(dst::Type::Struct(decl))
+            }
+            ast::TypeExpr::Instance { generic, args, .. } => {
+                let decl: Rc<RefCell<dst::r#struct::Decl>> = generic.resolve(scope)?;
+
+                if !decl.borrow().is_generic() {
+                    return Err(Panic::new(
+                        format!("{} is not generic", decl.borrow()),
+                        Some(Location::new(scope.unit(), self.span())),
+                    ));
+                }
+
+                ensure_type_args(&decl.borrow(), args.len(), self.span(), scope)?;
+
+                let args = args
+                    .iter()
+                    .map(|a| a.resolve(scope))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(dst::Type::Instance(decl, args))
+            }
+            ast::TypeExpr::Function {
+                params,
+                return_type,
+                ..
+            } => {
+                let params = params
+                    .iter()
+                    .map(|p| p.resolve(scope))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let return_type = match return_type {
+                    Some(return_type) => return_type.resolve(scope)?,
+                    None => dst::Type::Void,
+                };
+
+                Ok(dst::Type::Function(Rc::new(dst::FunctionType::new(
+                    params,
+                    return_type,
+                ))))
+            }
+            ast::TypeExpr::Optional { inner, .. } => {
+                Ok(dst::Type::Optional(Box::new(inner.resolve(scope)?)))
+            }
+            ast::TypeExpr::Pointer { inner, .. } => {
+                Ok(dst::Type::Pointer(Box::new(inner.resolve(scope)?)))
+            }
+            ast::TypeExpr::Tuple { elements, .. } => Ok(dst::Type::Tuple(
+                elements
+                    .iter()
+                    .map(|e| e.resolve(scope))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+        }
+    }
+}
+
+/// Onyx-panic unless a struct type takes `count` type arguments.
+fn ensure_type_args(
+    decl: &dst::r#struct::Decl,
+    count: usize,
+    span: Span,
+    scope: &dyn dst::Scope,
+) -> Result<(), Panic> {
+    let expected = decl.type_params().len();
+
+    if count == expected {
+        return Ok(());
+    }
+
+    let mut panic = Panic::new(
+        format!(
+            "{} expects {} type arguments, got {}",
+            decl, expected, count
+        ),
+        Some(Location::new(scope.unit(), span)),
+    );
+
+    if let Some(unit) = decl.unit.upgrade() {
+        panic.add_note(
+            "Declared here".to_string(),
+            Some(Location::new(unit, decl.id().span())),
+        );
+    }
+
+    Err(panic)
+}
+
+impl Resolve<Rc<dst::Closure>> for ast::Closure {
+    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<Rc<dst::Closure>, Panic> {
+        let mut captures: Vec<Rc<dst::Capture>> = vec![];
+
+        for capture in &self.captures {
+            if let Some(found) = captures.iter().find(|c| c.id == capture.id) {
+                let mut panic = Panic::new(
+                    format!("{} already captured", capture.id),
+                    Some(Location::new(scope.unit(), capture.span)),
+                );
+
+                panic.add_note(
+                    "Previously captured here".to_string(),
+                    Some(Location::new(scope.unit(), found.id.span())),
+                );
+
+                return Err(panic);
+            }
+
+            let target = scope.search(&capture.id).ok_or_else(|| {
+                Panic::new(
+                    format!("Undeclared {}", capture.id),
+                    Some(Location::new(scope.unit(), capture.id.span())),
+                )
+            })?;
+
+            scope.ensure_accessible(&capture.id, &target)?;
+
+            let r#type = match &target {
+                dst::Exportable::VarDecl(var) => {
+                    if capture.by_ref {
+                        // A narrowed variable must not change until the narrowing ends.
+                        if scope.search_narrowed(&target).is_some() {
+                            return Err(Panic::new(
+                                format!(
+                                    "Cannot capture {} by reference while it is narrowed",
+                                    capture.id
+                                ),
+                                Some(Location::new(scope.unit(), capture.span)),
+                            ));
+                        }
+
+                        var.captured_by_ref.set(true);
+                    }
+
+                    var.r#type.clone()
+                }
+                dst::Exportable::Param(param) => {
+                    if capture.by_ref {
+                        return Err(Panic::new(
+                            format!("Cannot capture parameter {} by reference", capture.id),
+                            Some(Location::new(scope.unit(), capture.span)),
+                        ));
+                    }
+
+                    param.used.set(true);
+                    param.r#type.clone()
+                }
+                dst::Exportable::Capture(outer) => {
+                    if capture.by_ref && !outer.by_ref {
+                        return Err(Panic::new(
+                            format!(
+                                "Cannot capture {} by reference, as it is captured by value",
+                                capture.id
+                            ),
+                            Some(Location::new(scope.unit(), capture.span)),
+                        ));
+                    }
+
+                    outer.used.set(true);
+                    outer.r#type.clone()
+                }
+                _ => {
+                    return Err(Panic::new(
+                        format!(
+                            "Cannot capture {}, only variables can be captured",
+                            capture.id
+                        ),
+                        Some(Location::new(scope.unit(), capture.id.span())),
+                    ))
+                }
+            };
+
+            captures.push(Rc::new(dst::Capture::new(
+                capture.id.clone(),
+                capture.by_ref,
+                r#type,
+                target,
+            )));
+        }
+
+        let mut params: Vec<Rc<dst::function::decl::Param>> = vec![];
+
+        for param in &self.params {
+            let param =
+                dst::function::decl::Param::new(param.id.clone(), param.r#type.resolve(scope)?);
+            params.push(Rc::new(param));
+        }
+
+        let return_type = match &self.return_type {
+            Some(return_type) => return_type.resolve(scope)?,
+            None => dst::Type::Void,
+        };
+
+        let r#type = Rc::new(dst::FunctionType::new(
+            params.iter().map(|p| p.r#type.clone()).collect(),
+            return_type,
+        ));
+
+        let mut closure_scope =
+            dst::ClosureScope::new(scope, captures.clone(), params.clone(), Rc::clone(&r#type));
+
+        let body = resolve_block(&self.body, &mut closure_scope)?;
+
+        if !r#type.return_type.is_void() && !body.last().is_some_and(|s| s.diverges()) {
+            return Err(Panic::new(
+                "Closure must end with a `return`".to_string(),
+                Some(Location::new(scope.unit(), self.span())),
+            ));
+        }
+
+        Ok(Rc::new(dst::Closure::new(
+            self.clone(),
+            captures,
+            params,
+            r#type,
+            body,
+        )))
+    }
+}
+
+impl Resolve<Rc<dst::Expr>> for ast::Expr {
+    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<Rc<dst::Expr>, Panic> {
+        match self {
+            ast::Expr::BoolLiteral(b) => Ok(Rc::new(dst::Expr::BoolLiteral(b.clone()))),
+            ast::Expr::StringLiteral(s) => Ok(Rc::new(dst::Expr::StringLiteral(s.clone()))),
+            ast::Expr::NilLiteral(n) => Ok(Rc::new(dst::Expr::NilLiteral(n.clone()))),
+            ast::Expr::Interpolation(i) => {
+                let format = resolve_segments(&i.segments, None, scope)?;
+                Ok(Rc::new(dst::Expr::Interpolation(dst::Interpolation::new(
+                    i.clone(),
+                    format,
+                ))))
+            }
+            ast::Expr::Ref(id) => {
+                let found = id.lookup(scope, &scope.unit())?;
+                let narrowed = scope.search_narrowed(&found);
+                let value = resolve_ref(id, found, scope)?;
+
+                match narrowed {
+                    Some(r#type) => Ok(Rc::new(dst::Expr::Unwrap(dst::Unwrap::new(value, r#type)))),
+                    None => Ok(value),
+                }
+            }
+            ast::Expr::Closure(closure) => Ok(Rc::new(dst::Expr::Closure(closure.resolve(scope)?))),
+            ast::Expr::MacroCall(m) => Ok(Rc::new(dst::Expr::MacroCall(m.resolve(scope)?))),
+            ast::Expr::Binop(b) => match b.op.as_str() {
+                "=" => {
+                    let lhs = b.lhs.resolve(scope)?;
+                    let rhs = b.rhs.resolve(scope)?;
+
+                    match &*lhs {
+                        dst::Expr::VarRef(r#ref)
+                            if r#ref
+                                .decl
+                                .unit
+                                .as_ref()
+                                .is_some_and(|u| !u.ptr_eq(&Rc::downgrade(&scope.unit()))) =>
+                        {
+                            let mut panic = Panic::new(
+                                format!("Cannot assign to imported variable {}", b.lhs),
+                                Some(Location::new(scope.unit(), lhs.span())),
+                            );
+
+                            let decl_unit = r#ref.decl.unit.as_ref().unwrap().upgrade().unwrap();
+                            panic.add_note(
+                                "Declared here".to_string(),
+                                Some(Location::new(decl_unit, r#ref.decl.id().span())),
+                            );
+
+                            return Err(panic);
+                        }
+                        dst::Expr::VarRef(_) => {}
+                        dst::Expr::CaptureRef(r#ref) if !r#ref.capture.by_ref => {
+                            return Err(Panic::new(
+                                format!(
+                                    "Cannot assign to {}, as it is captured by value",
+                                    r#ref.capture.id
+                                ),
+                                Some(Location::new(scope.unit(), lhs.span())),
+                            ));
+                        }
+                        dst::Expr::CaptureRef(_) => {}
+                        dst::Expr::Unwrap(_) => {
+                            return Err(Panic::new(
+                                format!("Cannot assign to {} while it is narrowed", b.lhs),
+                                Some(Location::new(scope.unit(), lhs.span())),
+                            ))
+                        }
+                        _ => {
+                            return Err(Panic::new(
+                                "Left-hand side of assignment must be a variable".to_string(),
+                                Some(Location::new(scope.unit(), lhs.span())),
+                            ))
+                        }
+                    }
+
+                    if rhs.infer_type(scope).is_void() {
+                        return Err(Panic::new(
+                            "Expression result must not be void".to_string(),
+                            Some(Location::new(scope.unit(), b.span())),
+                        ));
+                    }
+
+                    let lhs_type = lhs.infer_type(scope);
+                    let rhs = coerce_c_string(rhs, Some(&lhs_type));
+                    ensure_not_escaping(&rhs, scope)?;
+
+                    if !lhs_type.accepts(&rhs.infer_type(scope)) {
+                        return Err(Panic::new(
+                            format!(
+                                "Type mismatch: left is {}, right is {}",
+                                lhs_type,
+                                display_type(&rhs, scope)
+                            ),
+                            Some(Location::new(scope.unit(), rhs.span())),
+                        ));
+                    }
+
+                    Ok(Rc::new(dst::Expr::Assignment(dst::Assignment { lhs, rhs })))
+                }
+                "==" | "!=" => {
+                    let lhs = b.lhs.resolve(scope)?;
+                    let rhs = b.rhs.resolve(scope)?;
+
+                    let value = match (lhs.as_ref(), rhs.as_ref()) {
+                        (dst::Expr::NilLiteral(_), dst::Expr::NilLiteral(_)) => {
+                            return Err(Panic::new(
+                                "Cannot compare `nil` with `nil`".to_string(),
+                                Some(Location::new(scope.unit(), b.span())),
+                            ))
+                        }
+                        (_, dst::Expr::NilLiteral(_)) => lhs,
+                        (dst::Expr::NilLiteral(_), _) => rhs,
+                        _ => {
+                            let mut panic = Panic::new(
+                                "Only comparison with `nil` is supported".to_string(),
+                                Some(Location::new(scope.unit(), b.span())),
+                            );
+
+                            panic.add_note("Compare values with `eq?` instead".to_string(), None);
+                            return Err(panic);
+                        }
+                    };
+
+                    match value.infer_type(scope) {
+                        dst::Type::Optional(_) => {}
+                        r#type => {
+                            return Err(Panic::new(
+                                format!(
+                                    "Cannot compare {} with `nil`, as it is not optional",
+                                    r#type
+                                ),
+                                Some(Location::new(scope.unit(), value.span())),
+                            ))
+                        }
+                    }
+
+                    let bool_type = dst::Expr::BoolLiteral(ast::literal::Bool::new(b.span(), true))
+                        .infer_type(scope);
+
+                    Ok(Rc::new(dst::Expr::NilCheck(dst::NilCheck::new(
+                        b.clone(),
+                        value,
+                        b.op == "==",
+                        bool_type,
+                    ))))
+                }
+                &_ => todo!(),
+            },
+            ast::Expr::FunctionCall(call) => {
+                let call = resolve_call(call, scope)?;
+                let throws = call.throws();
+
+                if !throws.is_empty() {
+                    let mut panic = Panic::new(
+                        format!(
+                            "Unhandled error(s) {}",
+                            throws
+                                .iter()
+                                .map(|e| e.id().to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                        Some(Location::new(scope.unit(), call.span())),
+                    );
+
+                    panic.add_note(
+                        "Propagate with `try` or handle with `catch`".to_string(),
+                        None,
+                    );
+
+                    return Err(panic);
+                }
+
+                Ok(Rc::new(dst::Expr::FunctionCall(call)))
+            }
+            ast::Expr::Try(r#try) => {
+                let call = resolve_throwing_call(&r#try.expr, scope)?;
+
+                let function = scope.enclosing_function_type().ok_or_else(|| {
+                    let mut panic = Panic::new(
+                        "Cannot propagate errors outside of a function".to_string(),
+                        Some(Location::new(scope.unit(), r#try.span())),
+                    );
+
+                    panic.add_note("Handle with `catch` instead".to_string(), None);
+                    panic
+                })?;
+
+                for error in call.throws() {
+                    if !function.throws.contains(&error) {
+                        let mut panic = Panic::new(
+                            format!("Cannot propagate undeclared error {}", error.id()),
+                            Some(Location::new(scope.unit(), r#try.span())),
+                        );
+
+                        panic.add_note(
+                            format!("Add {} to the function `throws` clause", error.id()),
+                            None,
+                        );
+
+                        return Err(panic);
+                    }
+                }
+
+                Ok(Rc::new(dst::Expr::Try(dst::Try::new(
+                    r#try.clone(),
+                    Rc::new(dst::Expr::FunctionCall(call)),
+                ))))
+            }
+            ast::Expr::Catch(catch) => {
+                let call = resolve_throwing_call(&catch.expr, scope)?;
+                let fallback = catch.fallback.resolve(scope)?;
+
+                let expected = call.infer_type(scope);
+                let fallback = coerce_c_string(fallback, Some(&expected));
+
+                if !expected.accepts(&fallback.infer_type(scope)) && !fallback.diverges() {
+                    return Err(Panic::new(
+                        format!(
+                            "Type mismatch: expected {}, got {}",
+                            expected,
+                            display_type(&fallback, scope)
+                        ),
+                        Some(Location::new(scope.unit(), fallback.span())),
+                    ));
+                }
+
+                Ok(Rc::new(dst::Expr::Catch(dst::Catch::new(
+                    catch.clone(),
+                    Rc::new(dst::Expr::FunctionCall(call)),
+                    fallback,
+                ))))
+            }
+        }
+    }
+}
+
+/// Resolve a reference to a found entity as a value.
+fn resolve_ref(
+    id: &ast::Qualifier,
+    found: dst::Exportable,
+    scope: &dyn dst::Scope,
+) -> Result<Rc<dst::Expr>, Panic> {
+    match found {
+        dst::Exportable::VarDecl(var) => Ok(Rc::new(dst::Expr::VarRef(dst::VarRef::new(
+            id.id.clone(),
+            Rc::clone(&var),
+        )))),
+        dst::Exportable::StructDecl(_) => Err(Panic::new(
+            format!("Cannot use struct {} as a value", id),
+            Some(Location::new(scope.unit(), id.span())),
+        )),
+        dst::Exportable::FunctionDecl(decl) => {
+            if decl.borrow().is_variadic() {
+                return Err(Panic::new(
+                    format!("Cannot use variadic function {} as a value", id),
+                    Some(Location::new(scope.unit(), id.span())),
+                ));
+            }
+
+            if !decl.borrow().throws.is_empty() {
+                return Err(Panic::new(
+                    format!("Cannot use throwing function {} as a value", id),
+                    Some(Location::new(scope.unit(), id.span())),
+                ));
+            }
+
+            Ok(Rc::new(dst::Expr::FunctionRef(dst::FunctionRef::new(
+                id.clone(),
+                decl,
+            ))))
+        }
+        dst::Exportable::Namespace(_) => Err(Panic::new(
+            format!("Cannot use namespace {} as a value", id),
+            Some(Location::new(scope.unit(), id.span())),
+        )),
+        dst::Exportable::ErrorDecl(_) => Err(Panic::new(
+            format!("Cannot use error {} as a value", id),
+            Some(Location::new(scope.unit(), id.span())),
+        )),
+        dst::Exportable::Param(param) => {
+            param.used.set(true);
+
+            Ok(Rc::new(dst::Expr::ParamRef(dst::ParamRef::new(
+                id.id.clone(),
+                param,
+            ))))
+        }
+        dst::Exportable::Capture(capture) => {
+            capture.used.set(true);
+
+            Ok(Rc::new(dst::Expr::CaptureRef(dst::CaptureRef::new(
+                id.id.clone(),
+                capture,
+            ))))
+        }
+    }
+}
+
+/// Resolve a function call, not checking for thrown errors.
+fn resolve_call(call: &ast::Call, scope: &mut dyn dst::Scope) -> Result<dst::Call, Panic> {
+    let mut args: Vec<Rc<dst::Expr>> = vec![];
+
+    let callee = if let Some(receiver) = &call.receiver {
+        if let Some(container) = &call.callee.container {
+            return Err(Panic::new(
+                "Instance function call must not be qualified".to_string(),
+                Some(Location::new(scope.unit(), container.span())),
+            ));
+        }
+
+        let receiver = receiver.resolve(scope)?;
+
+        let r#type = receiver.infer_type(scope);
+
+        if r#type.is_void() {
+            return Err(Panic::new(
+                "Expression result must not be void".to_string(),
+                Some(Location::new(scope.unit(), receiver.span())),
+            ));
+        }
+
+        let r#struct = r#type.as_struct().ok_or_else(|| {
+            Panic::new(
+                format!("Cannot call {} on {}", call.callee, r#type),
+                Some(Location::new(scope.unit(), call.callee.span())),
+            )
+        })?;
+
+        let callee = qualifier::search_struct_function(r#struct, &call.callee.id, &scope.unit())?
+            .ok_or_else(|| {
+            Panic::new(
+                format!("Undeclared {} for {}", call.callee, r#struct.borrow().id()),
+                Some(Location::new(scope.unit(), call.callee.span())),
+            )
+        })?;
+
+        if !callee.borrow().is_instance() {
+            return Err(Panic::new(
+                format!("{} is not an instance function", call.callee),
+                Some(Location::new(scope.unit(), call.callee.span())),
+            ));
+        }
+
+        args.push(receiver);
+        dst::Callee::Function(callee)
+    } else {
+        match call.callee.lookup(scope, &scope.unit())? {
+            dst::Exportable::FunctionDecl(decl) => dst::Callee::Function(decl),
+            dst::Exportable::StructDecl(_)
+            | dst::Exportable::Namespace(_)
+            | dst::Exportable::ErrorDecl(_) => {
+                return Err(Panic::new(
+                    format!("{} is not a function", call.callee),
+                    Some(Location::new(scope.unit(), call.callee.span())),
+                ))
+            }
+            _ => {
+                let value = ast::Expr::Ref(call.callee.clone()).resolve(scope)?;
+
+                match value.infer_type(scope) {
+                    dst::Type::Function(r#type) => dst::Callee::Value(value, r#type),
+                    r#type => {
+                        return Err(Panic::new(
+                            format!("{} is not a function, but {}", call.callee, r#type),
+                            Some(Location::new(scope.unit(), call.callee.span())),
+                        ))
+                    }
+                }
+            }
+        }
+    };
+
+    let r#type = match &callee {
+        dst::Callee::Function(decl) => Rc::new(decl.borrow().r#type()),
+        dst::Callee::Value(_, r#type) => Rc::clone(r#type),
+    };
+
+    let variadic = matches!(&callee, dst::Callee::Function(decl) if decl.borrow().is_variadic());
+
+    // A receiver is already resolved as the first argument.
+    for arg in &call.args {
+        let arg = arg.resolve(scope)?;
+        ensure_not_escaping(&arg, scope)?;
+
+        match r#type.params.get(args.len()) {
+            Some(param) => args.push(coerce_c_string(arg, Some(param))),
+            None if variadic => args.push(coerce_c_string(arg, None)),
+            None => args.push(arg),
+        }
+    }
+
+    if variadic && args.len() < r#type.params.len() {
+        return Err(Panic::new(
+            format!(
+                "Expected at least {} arguments, got {}",
+                r#type.params.len(),
+                args.len()
+            ),
+            Some(Location::new(scope.unit(), call.span())),
+        ));
+    } else if !variadic && args.len() != r#type.params.len() {
+        return Err(Panic::new(
+            format!(
+                "Expected {} arguments, got {}",
+                r#type.params.len(),
+                args.len()
+            ),
+            Some(Location::new(scope.unit(), call.span())),
+        ));
+    }
+
+    // C variadic arguments may be of any FFI-safe type,
+    // `Bool` is promoted to `int` when lowered.
+    for arg in &args[r#type.params.len()..] {
+        let arg_type = arg.infer_type(scope);
+
+        if arg_type.is_void() {
+            return Err(Panic::new(
+                "Expression result must not be void".to_string(),
+                Some(Location::new(scope.unit(), arg.span())),
+            ));
+        }
+
+        ensure_ffi_safe(&arg_type, arg.span(), scope)?;
+    }
+
+    for (arg, param) in args.iter().zip(r#type.params.iter()) {
+        let arg_type = arg.infer_type(scope);
+
+        if arg_type.is_void() {
+            return Err(Panic::new(
+                "Expression result must not be void".to_string(),
+                Some(Location::new(scope.unit(), arg.span())),
+            ));
+        }
+
+        if !param.accepts(&arg_type) {
+            return Err(Panic::new(
+                format!(
+                    "Type mismatch: expected {}, got {}",
+                    param,
+                    display_type(arg, scope)
+                ),
+                Some(Location::new(scope.unit(), arg.span())),
+            ));
+        }
+    }
+
+    Ok(dst::Call::new(call.clone(), callee, args))
+}
+
+/// Resolve the operand of `try` or `catch`, which must be a throwing call.
+fn resolve_throwing_call(expr: &ast::Expr, scope: &mut dyn dst::Scope) -> Result<dst::Call, Panic> {
+    let ast::Expr::FunctionCall(call) = expr else {
+        return Err(Panic::new(
+            "Expected a function call".to_string(),
+            Some(Location::new(scope.unit(), expr.span())),
+        ));
+    };
+
+    let call = resolve_call(call, scope)?;
+
+    if call.throws().is_empty() {
+        return Err(Panic::new(
+            format!("{} never throws", call.ast_node.callee),
+            Some(Location::new(scope.unit(), call.span())),
+        ));
+    }
+
+    Ok(call)
+}
+
+/// Coerce a string literal to a `CString` if it's `expected`,
+/// or if it's a C variadic argument, i.e. nothing is expected.
+fn coerce_c_string(expr: Rc<dst::Expr>, expected: Option<&dst::Type>) -> Rc<dst::Expr> {
+    match expr.as_ref() {
+        dst::Expr::StringLiteral(s) if expected.is_none_or(|t| t.is_c_string()) => {
+            Rc::new(dst::Expr::CStringLiteral(s.clone()))
+        }
+        _ => expr,
+    }
+}
+
+/// Display the type of `expr` for a type mismatch message.
+fn display_type(expr: &dst::Expr, scope: &dyn dst::Scope) -> String {
+    match expr {
+        dst::Expr::NilLiteral(_) => "`nil`".to_string(),
+        _ => expr.infer_type(scope).to_string(),
+    }
+}
+
+impl Resolve<Rc<dst::VarDecl>> for ast::VarDecl {
+    /// Resolve a local variable declaration.
+    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<Rc<dst::VarDecl>, Panic> {
+        if self.r#pub {
+            return Err(Panic::new(
+                "Only module-level variables may be `pub`".to_string(),
+                Some(Location::new(scope.unit(), self.span())),
+            ));
+        }
+
+        let (r#type, expr) = resolve_var_value(self, scope)?;
+        Ok(Rc::new(dst::VarDecl::new(self.clone(), r#type, expr)))
+    }
+}
+
+/// Resolve a variable initializer and its type.
+fn resolve_var_value(
+    var_decl: &ast::VarDecl,
+    scope: &mut dyn dst::Scope,
+) -> Result<(dst::Type, Rc<dst::Expr>), Panic> {
+    // TODO: Apply decorators.
+    let expr = var_decl.expr.resolve(scope)?;
+
+    if let dst::Expr::NilLiteral(nil) = expr.as_ref() {
+        return Err(Panic::new(
+            "Cannot infer the type of `nil`".to_string(),
+            Some(Location::new(scope.unit(), nil.span())),
+        ));
+    }
+
+    let r#type = expr.infer_type(scope);
+
+    if r#type.is_void() {
+        return Err(Panic::new(
+            "Expression returns void".to_string(),
+            Some(Location::new(scope.unit(), expr.span())),
+        ));
+    }
+
+    if r#type.is_never() {
+        return Err(Panic::new(
+            "Expression never returns".to_string(),
+            Some(Location::new(scope.unit(), expr.span())),
+        ));
+    }
+
+    Ok((r#type, expr))
+}
+
+impl Resolve<dst::MacroCall> for ast::MacroCall {
+    fn resolve(&self, scope: &mut dyn dst::Scope) -> Result<dst::MacroCall, Panic> {
+        match self.id.value.as_str() {
+            "assert" => {
+                expect_macro_args(self, 1, 2, scope)?;
+                let condition = self.args[0].resolve(scope)?;
+                let r#type = condition.infer_type(scope);
+
+                if !r#type.is_bool() {
+                    return Err(Panic::new(
+                        "Asserted expression must be a `Bool`".to_string(),
+                        Some(Location::new(scope.unit(), self.args[0].span())),
+                    ));
+                }
+
+                let source = scope.unit().borrow().source();
+                let span = self.args[0].span();
+                let text = format!(
+                    "Assertion failed: `{}`",
+                    &source[span.start.offset..span.end.offset]
+                );
+
+                let report = resolve_report(self, text, self.args.get(1), scope)?;
+                Ok(dst::MacroCall::Assert(self.clone(), condition, report))
+            }
+            "panic" => {
+                expect_macro_args(self, 1, 1, scope)?;
+                let report =
+                    resolve_report(self, "Panicked".to_string(), self.args.first(), scope)?;
+                Ok(dst::MacroCall::Panic(self.clone(), report))
+            }
+            "todo" => {
+                expect_macro_args(self, 0, 1, scope)?;
+                let text = "Not implemented yet".to_string();
+                let report = resolve_report(self, text, self.args.first(), scope)?;
+                Ok(dst::MacroCall::Todo(self.clone(), report))
+            }
+            "unreachable" => {
+                expect_macro_args(self, 0, 1, scope)?;
+                let text = "Reached unreachable code".to_string();
+                let report = resolve_report(self, text, self.args.first(), scope)?;
+                Ok(dst::MacroCall::Unreachable(self.clone(), report))
+            }
+            "print" => Ok(dst::MacroCall::Print(
+                self.clone(),
+                resolve_format(self, scope)?,
+            )),
+            "println" => Ok(dst::MacroCall::Println(
+                self.clone(),
+                resolve_format(self, scope)?,
+            )),
+            "format" => Ok(dst::MacroCall::Format(
+                self.clone(),
+                resolve_format(self, scope)?,
+            )),
+            _ => Err(Panic::new(
+                format!("Unknown macro: {}", self.id.value),
+                Some(Location::new(scope.unit(), self.id.span())),
+            )),
+        }
+    }
+}
+
+/// Resolve `@print`-like macro arguments, i.e. a format string literal
+/// followed by the arguments to substitute its `{}` placeholders with.
+fn resolve_format(
+    macro_call: &ast::MacroCall,
+    scope: &mut dyn dst::Scope,
+) -> Result<dst::Format, Panic> {
+    let segments = match macro_call.args.first() {
+        Some(ast::Expr::StringLiteral(literal)) => {
+            vec![ast::interpolation::Segment::Text(literal.value.clone())]
+        }
+        Some(ast::Expr::Interpolation(interpolation)) => interpolation.segments.clone(),
+        _ => {
+            return Err(Panic::new(
+                format!(
+                    "`@{}` expects a format string literal as the first argument",
+                    macro_call.id.value
+                ),
+                Some(Location::new(scope.unit(), macro_call.span())),
+            ))
+        }
+    };
+
+    resolve_segments(&segments, Some(&macro_call.args[1..]), scope)
+}
+
+/// Resolve interpolated string segments into a format.
+/// `{}` placeholders take `positional` arguments in order,
+/// which are only available in format macros.
+fn resolve_segments(
+    segments: &[ast::interpolation::Segment],
+    positional: Option<&[ast::Expr]>,
+    scope: &mut dyn dst::Scope,
+) -> Result<dst::Format, Panic> {
+    let mut texts = vec![String::new()];
+    let mut args = Vec::new();
+    let mut positional_iter = positional.unwrap_or_default().iter();
+
+    for segment in segments {
+        let arg = match segment {
+            ast::interpolation::Segment::Text(text) => {
+                texts.last_mut().unwrap().push_str(text);
+                continue;
+            }
+            ast::interpolation::Segment::Expr(expr) => expr,
+            ast::interpolation::Segment::Placeholder(span) => {
+                if positional.is_none() {
+                    let mut panic = Panic::new(
+                        "Empty interpolation".to_string(),
+                        Some(Location::new(scope.unit(), *span)),
+                    );
+
+                    panic.add_note(
+                        "Interpolate an expression, e.g. `{x}`, or escape braces as `{{` and `}}`"
+                            .to_string(),
+                        None,
+                    );
+
+                    return Err(panic);
+                }
+
+                positional_iter.next().ok_or_else(|| {
+                    Panic::new(
+                        "Missing argument for the placeholder".to_string(),
+                        Some(Location::new(scope.unit(), *span)),
+                    )
+                })?
+            }
+        };
+
+        args.push(resolve_format_arg(arg, scope)?);
+        texts.push(String::new());
+    }
+
+    if let Some(unused) = positional_iter.next() {
+        return Err(Panic::new(
+            "Unused format argument".to_string(),
+            Some(Location::new(scope.unit(), unused.span())),
+        ));
+    }
+
+    Ok(dst::Format::new(texts, args))
+}
+
+/// Resolve a formatted argument, which must have a struct type.
+fn resolve_format_arg(
+    arg: &ast::Expr,
+    scope: &mut dyn dst::Scope,
+) -> Result<(Rc<dst::Expr>, dst::Type), Panic> {
+    let expr = arg.resolve(scope)?;
+
+    match expr.infer_type(scope) {
+        r#type @ dst::Type::Struct(_) => Ok((expr, r#type)),
+        dst::Type::Void | dst::Type::Never => Err(Panic::new(
+            "Cannot format an expression without value".to_string(),
+            Some(Location::new(scope.unit(), arg.span())),
+        )),
+        r#type => Err(Panic::new(
+            format!("Cannot format a value of {}", r#type),
+            Some(Location::new(scope.unit(), arg.span())),
+        )),
+    }
+}
+
+/// Ensure a macro is called with `min..=max` arguments.
+fn expect_macro_args(
+    macro_call: &ast::MacroCall,
+    min: usize,
+    max: usize,
+    scope: &dyn dst::Scope,
+) -> Result<(), Panic> {
+    if (min..=max).contains(&macro_call.args.len()) {
+        return Ok(());
+    }
+
+    let expected = if min == max {
+        min.to_string()
+    } else {
+        format!("{} to {}", min, max)
+    };
+
+    Err(Panic::new(
+        format!(
+            "`@{}` expects {} argument(s), got {}",
+            macro_call.id.value,
+            expected,
+            macro_call.args.len()
+        ),
+        Some(Location::new(scope.unit(), macro_call.span())),
+    ))
+}
+
+/// Resolve a runtime panic report at the macro call location,
+/// with an optional `String` message.
+fn resolve_report(
+    macro_call: &ast::MacroCall,
+    text: String,
+    message: Option<&ast::Expr>,
+    scope: &mut dyn dst::Scope,
+) -> Result<dst::Report, Panic> {
+    let message = match message {
+        Some(message) => {
+            let expr = message.resolve(scope)?;
+            let r#type = expr.infer_type(scope);
+
+            if !r#type.is_string() {
+                return Err(Panic::new(
+                    "Panic message must be a `String`".to_string(),
+                    Some(Location::new(scope.unit(), message.span())),
+                ));
+            }
+
+            Some(expr)
+        }
+        None => None,
+    };
+
+    let location = Location::new(scope.unit(), macro_call.span()).to_string();
+    Ok(dst::Report::new(text, message, location))
 }
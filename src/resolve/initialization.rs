@@ -0,0 +1,203 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    dst::{self, HasId, Scope},
+    location::{HasSpan, Span},
+    unit::Unit,
+    Location, Panic,
+};
+
+/// Ensure the unit initialization reads no module-level variable of the unit
+/// before it's initialized, either directly or through functions of the unit.
+/// Conservatively, a function referenced is assumed to be called,
+/// and a closure created is assumed to be called right away.
+pub fn ensure_initialized(module: &dst::Mod) -> Result<(), Panic> {
+    let unit = module.unit();
+
+    let mut init = Init {
+        unit: Rc::downgrade(&unit),
+        initialized: Vec::new(),
+        walked: Vec::new(),
+        entry: None,
+    };
+
+    for stmt in &module.main {
+        let read = match stmt {
+            dst::Statement::VarDecl(var) => {
+                let read = init.expr(&var.expr);
+                init.initialized.push(Rc::clone(var));
+                read
+            }
+            _ => init.statements(std::slice::from_ref(stmt)),
+        };
+
+        if let Some(read) = read {
+            return Err(read.into_panic(unit));
+        }
+    }
+
+    Ok(())
+}
+
+/// A read of a module-level variable before its initialization.
+struct Read {
+    var: Rc<dst::VarDecl>,
+    span: Span,
+
+    /// The top-level expression calling the function which reads the variable.
+    entry: Option<Span>,
+}
+
+impl Read {
+    fn into_panic(self, unit: Rc<RefCell<Unit>>) -> Panic {
+        let mut panic = Panic::new(
+            format!("{} may be read before its initialization", self.var.id()),
+            Some(Location::new(
+                Rc::clone(&unit),
+                self.entry.unwrap_or(self.span),
+            )),
+        );
+
+        if self.entry.is_some() {
+            panic.add_note(
+                "Read here".to_string(),
+                Some(Location::new(Rc::clone(&unit), self.span)),
+            );
+        }
+
+        panic.add_note(
+            "Initialized here".to_string(),
+            Some(Location::new(unit, self.var.id().span())),
+        );
+
+        panic
+    }
+}
+
+/// The state of the unit initialization walk.
+struct Init {
+    unit: Weak<RefCell<Unit>>,
+
+    /// Module-level variables initialized so far, in order.
+    initialized: Vec<Rc<dst::VarDecl>>,
+
+    /// Functions of the unit already walked. As variables are only ever
+    /// added to `initialized`, a function need not be walked twice.
+    walked: Vec<Rc<RefCell<dst::function::Decl>>>,
+
+    /// The top-level expression the walk entered a function body from.
+    entry: Option<Span>,
+}
+
+impl Init {
+    fn statements(&mut self, statements: &[dst::Statement]) -> Option<Read> {
+        statements.iter().find_map(|stmt| match stmt {
+            dst::Statement::VarDecl(var) => self.expr(&var.expr),
+            dst::Statement::TerminatedExpr(expr) => self.expr(expr),
+            dst::Statement::Return(expr) => expr.as_ref().and_then(|e| self.expr(e)),
+            dst::Statement::Throw(_) => None,
+            dst::Statement::If(r#if) => {
+                let condition = match &r#if.condition {
+                    dst::Condition::Expr(expr) => expr,
+                    dst::Condition::Let(var) => &var.expr,
+                };
+
+                self.expr(condition)
+                    .or_else(|| self.statements(&r#if.then))
+                    .or_else(|| self.statements(r#if.r#else.as_deref().unwrap_or_default()))
+            }
+        })
+    }
+
+    fn expr(&mut self, expr: &dst::Expr) -> Option<Read> {
+        match expr {
+            dst::Expr::BoolLiteral(_)
+            | dst::Expr::StringLiteral(_)
+            | dst::Expr::CStringLiteral(_)
+            | dst::Expr::NilLiteral(_)
+            | dst::Expr::ParamRef(_)
+            | dst::Expr::CaptureRef(_) => None,
+            dst::Expr::Interpolation(i) => self.format(&i.format),
+            dst::Expr::VarRef(r) => {
+                let uninitialized = r.decl.unit.as_ref().is_some_and(|u| u.ptr_eq(&self.unit))
+                    && !self.initialized.iter().any(|v| Rc::ptr_eq(v, &r.decl));
+
+                uninitialized.then(|| Read {
+                    var: Rc::clone(&r.decl),
+                    span: r.span(),
+                    entry: self.entry,
+                })
+            }
+            dst::Expr::FunctionRef(r) => self.function(&r.decl, r.span()),
+            dst::Expr::MacroCall(m) => match m {
+                dst::MacroCall::Assert(_, condition, report) => {
+                    self.expr(condition).or_else(|| self.report(report))
+                }
+                dst::MacroCall::Panic(_, report)
+                | dst::MacroCall::Todo(_, report)
+                | dst::MacroCall::Unreachable(_, report) => self.report(report),
+                dst::MacroCall::Print(_, format)
+                | dst::MacroCall::Println(_, format)
+                | dst::MacroCall::Format(_, format) => self.format(format),
+            },
+            dst::Expr::FunctionCall(call) => {
+                let callee = match &call.callee {
+                    dst::Callee::Function(decl) => self.function(decl, call.span()),
+                    dst::Callee::Value(value, _) => self.expr(value),
+                };
+
+                callee.or_else(|| call.args.iter().find_map(|arg| self.expr(arg)))
+            }
+            dst::Expr::Assignment(a) => {
+                // Assigning a variable doesn't read it.
+                let lhs = match a.lhs.as_ref() {
+                    dst::Expr::VarRef(_) => None,
+                    lhs => self.expr(lhs),
+                };
+
+                lhs.or_else(|| self.expr(&a.rhs))
+            }
+            dst::Expr::Closure(c) => self.body(&c.body, c.span()),
+            dst::Expr::Try(t) => self.expr(&t.call),
+            dst::Expr::Catch(c) => self.expr(&c.call).or_else(|| self.expr(&c.fallback)),
+            dst::Expr::NilCheck(c) => self.expr(&c.value),
+            dst::Expr::Unwrap(u) => self.expr(&u.value),
+        }
+    }
+
+    /// Walk a function of the unit, unless already walked.
+    /// Functions of other units are not walked, as their units are initialized first.
+    fn function(&mut self, decl: &Rc<RefCell<dst::function::Decl>>, span: Span) -> Option<Read> {
+        if !decl.borrow().unit.ptr_eq(&self.unit) || self.walked.iter().any(|f| Rc::ptr_eq(f, decl))
+        {
+            return None;
+        }
+
+        self.walked.push(Rc::clone(decl));
+
+        match &decl.borrow().body {
+            Some(body) => self.body(body, span),
+            None => None,
+        }
+    }
+
+    /// Walk a function or closure body, entered from `span`.
+    fn body(&mut self, body: &[dst::Statement], span: Span) -> Option<Read> {
+        let entry = self.entry;
+        self.entry = entry.or(Some(span));
+        let read = self.statements(body);
+        self.entry = entry;
+        read
+    }
+
+    fn format(&mut self, format: &dst::Format) -> Option<Read> {
+        format.args.iter().find_map(|(arg, _)| self.expr(arg))
+    }
+
+    fn report(&mut self, report: &dst::Report) -> Option<Read> {
+        report.message.as_ref().and_then(|m| self.expr(m))
+    }
+}
@@ -1,20 +1,176 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crate::{ast, dst, location::HasSpan, Location, Panic};
+use crate::{
+    ast,
+    dst::{self, HasId, Scope},
+    location::HasSpan,
+    unit::Unit,
+    Location, Panic,
+};
 
 use super::Resolve;
 
+impl ast::Qualifier {
+    /// Look the qualifier up in the scope,
+    /// walking nested namespaces and struct functions.
+    /// The `unit` is the unit containing the qualifier.
+    pub fn lookup(
+        &self,
+        scope: &dyn dst::Scope,
+        unit: &Rc<RefCell<Unit>>,
+    ) -> Result<dst::Exportable, Panic> {
+        let container = match &self.container {
+            Some(container) => container.lookup(scope, unit)?,
+            None => {
+                let found = scope.search(&self.id).ok_or_else(|| {
+                    let message = if self.id.value == "Self" {
+                        "`Self` is only available within a struct".to_string()
+                    } else {
+                        format!("Undeclared {}", self)
+                    };
+
+                    Panic::new(message, Some(Location::new(unit.clone(), self.span())))
+                })?;
+
+                scope.ensure_accessible(&self.id, &found)?;
+                return Ok(found);
+            }
+        };
+
+        self.lookup_member(container, unit)
+    }
+
+    /// Look the qualifier up in the exports of an imported `module`.
+    /// Only `pub` declarations may be imported, and the builtin
+    /// fallback of the module is not searched.
+    /// The `unit` is the importing unit.
+    pub fn lookup_export(
+        &self,
+        module: &dst::Mod,
+        unit: &Rc<RefCell<Unit>>,
+    ) -> Result<dst::Exportable, Panic> {
+        let container = match &self.container {
+            Some(container) => container.lookup_export(module, unit)?,
+            None => {
+                if let Some(found) = module.exports.get(&self.id.value) {
+                    return Ok(found.clone());
+                }
+
+                let path = module.path();
+
+                if let Some(found) = module.declarations.get(&self.id.value) {
+                    let mut panic = Panic::new(
+                        format!("{} is private to \"{}\"", self.id, path.display()),
+                        Some(Location::new(unit.clone(), self.id.span())),
+                    );
+
+                    panic.add_note(
+                        "Declared here".to_string(),
+                        Some(Location::new(module.unit(), found.id().span())),
+                    );
+
+                    panic.add_note(format!("Declare {} with `pub` to import it", self.id), None);
+
+                    return Err(panic);
+                }
+
+                return Err(Panic::new(
+                    format!("{} not exported from \"{}\"", self.id, path.display()),
+                    Some(Location::new(unit.clone(), self.id.span())),
+                ));
+            }
+        };
+
+        self.lookup_member(container, unit)
+    }
+
+    /// Look the qualifier id up as a member of an already found `container`.
+    fn lookup_member(
+        &self,
+        container: dst::Exportable,
+        unit: &Rc<RefCell<Unit>>,
+    ) -> Result<dst::Exportable, Panic> {
+        match container {
+            dst::Exportable::Namespace(namespace) => {
+                let namespace = namespace.borrow();
+
+                if let Some(found) = namespace.exports.get(&self.id.value) {
+                    Ok(found.clone())
+                } else if namespace.declarations.contains_key(&self.id.value) {
+                    let mut panic = Panic::new(
+                        format!("{} is private to namespace {}", self.id, namespace.id()),
+                        Some(Location::new(unit.clone(), self.id.span())),
+                    );
+
+                    panic.add_note(format!("Declare {} with `pub` to access it", self.id), None);
+
+                    Err(panic)
+                } else {
+                    Err(Panic::new(
+                        format!("Undeclared {} in namespace {}", self.id, namespace.id()),
+                        Some(Location::new(unit.clone(), self.id.span())),
+                    ))
+                }
+            }
+            dst::Exportable::StructDecl(decl) => search_struct_function(&decl, &self.id, unit)?
+                .map(dst::Exportable::FunctionDecl)
+                .ok_or_else(|| {
+                    Panic::new(
+                        format!("Undeclared {} for {}", self.id, decl.borrow().id()),
+                        Some(Location::new(unit.clone(), self.id.span())),
+                    )
+                }),
+            _ => Err(Panic::new(
+                format!(
+                    "{} is neither a namespace nor a struct",
+                    self.container.as_ref().unwrap()
+                ),
+                Some(Location::new(
+                    unit.clone(),
+                    self.container.as_ref().unwrap().span(),
+                )),
+            )),
+        }
+    }
+}
+
+/// Search for a struct function visible from `unit`.
+/// Onyx-panics if the function is ambiguous.
+pub fn search_struct_function(
+    decl: &Rc<RefCell<dst::r#struct::Decl>>,
+    id: &ast::Id,
+    unit: &Rc<RefCell<Unit>>,
+) -> Result<Option<Rc<RefCell<dst::function::Decl>>>, Panic> {
+    let found = decl.borrow().search_functions(id, unit);
+
+    if found.len() > 1 {
+        let mut panic = Panic::new(
+            format!("Ambiguous {} for {}", id, decl.borrow().id()),
+            Some(Location::new(unit.clone(), id.span())),
+        );
+
+        for candidate in &found {
+            panic.add_note(
+                "Candidate declared here".to_string(),
+                Some(Location::new(
+                    candidate.borrow().unit.upgrade().unwrap(),
+                    candidate.borrow().id().span(),
+                )),
+            );
+        }
+
+        return Err(panic);
+    }
+
+    Ok(found.into_iter().next())
+}
+
 impl Resolve<Rc<RefCell<dst::r#struct::Decl>>> for ast::Qualifier {
     fn resolve(
         &self,
         scope: &mut dyn dst::Scope,
     ) -> Result<Rc<RefCell<dst::r#struct::Decl>>, Panic> {
-        let found = scope.search(&self.id).ok_or_else(|| {
-            Panic::new(
-                format!("Undeclared {}", self),
-                Some(Location::new(scope.unit(), self.span())),
-            )
-        })?;
+        let found = self.lookup(scope, &scope.unit())?;
 
         if let dst::Exportable::StructDecl(decl) = found {
             Ok(decl)
@@ -32,12 +188,7 @@ impl Resolve<Rc<RefCell<dst::function::Decl>>> for ast::Qualifier {
         &self,
         scope: &mut dyn dst::Scope,
     ) -> Result<Rc<RefCell<dst::function::Decl>>, Panic> {
-        let found = scope.search(&self.id).ok_or_else(|| {
-            Panic::new(
-                format!("Undeclared {}", self),
-                Some(Location::new(scope.unit(), self.span())),
-            )
-        })?;
+        let found = self.lookup(scope, &scope.unit())?;
 
         if let dst::Exportable::FunctionDecl(decl) = found {
             Ok(decl)
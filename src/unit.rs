@@ -1,6 +1,13 @@
-use crate::{ast, dst, lower::Lowerable, parser, program::Program, Panic};
+use crate::{
+    ast, dst,
+    lower::{self, Lowerable},
+    parser,
+    program::Program,
+    Panic,
+};
 use std::{
     cell::RefCell,
+    io::Write,
     path::PathBuf,
     rc::{Rc, Weak},
 };
@@ -28,14 +35,36 @@ impl Unit {
         }))
     }
 
+    /// Create a unit with its source already loaded, e.g. for tests.
+    pub fn with_source(
+        program: Weak<RefCell<Program>>,
+        path: PathBuf,
+        source: String,
+    ) -> Rc<RefCell<Self>> {
+        let unit = Self::new(program, path);
+        unit.borrow_mut().source = Some(Rc::new(source));
+        unit
+    }
+
+    /// Return the module name, e.g. `collections/list`.
+    /// See [`Program::module_name`].
+    pub fn name(&self) -> String {
+        match self.program.upgrade() {
+            Some(program) => program.borrow().module_name(&self.path),
+            None => self.path.with_extension("").display().to_string(),
+        }
+    }
+
     pub fn try_source(&mut self) -> Result<Rc<String>, Panic> {
-        if self.source.is_some() {
-            return Ok(self.source.as_ref().unwrap().clone());
+        if let Some(source) = &self.source {
+            return Ok(source.clone());
         }
 
         self.source = Some(Rc::new(match self.path.to_str() {
             Some("builtin") => include_str!("../lang/builtin.nx").to_string(),
             Some("builtin/bool") => include_str!("../lang/builtin/bool.nx").to_string(),
+            Some("builtin/c") => include_str!("../lang/builtin/c.nx").to_string(),
+            Some("builtin/string") => include_str!("../lang/builtin/string.nx").to_string(),
             _ => {
                 let source = std::fs::read_to_string(&self.path);
 
@@ -88,9 +117,12 @@ impl Unit {
         Ok(())
     }
 
-    pub fn lower(&mut self, cache_path: PathBuf) -> PathBuf {
-        if self.lowered_path.is_some() {
-            return self.lowered_path.as_ref().unwrap().to_path_buf(); // Already lowered
+    /// Lower the unit and its dependencies, returning the lowered path.
+    /// The entry unit is given the order to initialize other units in,
+    /// see [`lower::write_entry_point`].
+    pub fn lower(&mut self, cache_path: PathBuf, init_order: Option<&[String]>) -> PathBuf {
+        if let Some(lowered_path) = &self.lowered_path {
+            return lowered_path.to_path_buf(); // Already lowered
         }
 
         // Lower all dependencies first.
@@ -99,7 +131,7 @@ impl Unit {
                 .upgrade()
                 .unwrap()
                 .borrow_mut()
-                .lower(cache_path.clone());
+                .lower(cache_path.clone(), None);
         }
 
         let lowering_path = cache_path.join(self.hash()).with_extension("zig");
@@ -110,11 +142,30 @@ impl Unit {
         );
 
         let mut file = std::fs::File::create(&lowering_path).unwrap();
-        let result = self
-            .dst
-            .as_ref()
-            .expect("Unit must be resolved")
-            .lower(&mut file);
+        let dst = self.dst.as_ref().expect("Unit must be resolved");
+        let ctx = lower::Context::new(dst.unit.clone());
+        let mut result = dst.lower(&mut file, &ctx);
+
+        // Reference dependencies so that their exported functions are emitted.
+        let mut hashes: Vec<_> = self
+            .dependencies
+            .values()
+            .map(|dependency| dependency.upgrade().unwrap().borrow().hash())
+            .collect();
+        hashes.sort();
+
+        for hash in hashes {
+            if result.is_ok() {
+                result = writeln!(file, "comptime {{ _ = @import(\"{}.zig\"); }}", hash);
+            }
+        }
+
+        if let Some(init_order) = init_order {
+            if result.is_ok() {
+                result = lower::write_entry_point(&mut file, init_order);
+            }
+        }
+
         if result.is_err() {
             panic!(
                 "Failed to lower \"{}\" to \"{}\": {}",
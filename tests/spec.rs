@@ -1,9 +1,20 @@
-use nx::program::Program;
+use nx::{
+    manifest::Manifest,
+    package::{self, Lockfile},
+    program::Program,
+};
 
-fn assert_panic(path: &str) {
+fn assert_panic(path: &str, expected: &str) {
     let program = Program::new(".cache".into());
-    let result = Program::run(program, path.into(), "zig".into());
-    assert!(result.is_err());
+
+    match Program::run(program, path.into(), "zig".into()) {
+        Ok(()) => panic!("Expected {} to panic", path),
+        Err(panic) => assert!(
+            panic.message.contains(expected),
+            "Unexpected panic: {}",
+            panic.message
+        ),
+    }
 }
 
 #[test]
@@ -12,12 +23,437 @@ fn assert() {
     Program::run(program, "lang/spec/assert.nx".into(), "zig".into()).unwrap()
 }
 
+#[test]
+fn struct_impl() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/impl.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn namespace() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/namespace.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn closure() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/closure.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn ffi() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/ffi.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn cimport() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/cimport.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn export() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/export.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn print() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/print.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn interpolation() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/interpolation.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+#[should_panic(expected = "Failed to run")]
+fn runtime_panic() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/runtime-panic.nx".into(), "zig".into()).unwrap()
+}
+
 #[test]
 fn panic_unused_expression() {
-    assert_panic("lang/spec/panic-unused-expression-result.nx");
+    assert_panic(
+        "lang/spec/panic-unused-expression-result.nx",
+        "Unused expression result",
+    );
 }
 
 #[test]
 fn panic_variable_not_found() {
-    assert_panic("lang/spec/panic-variable-not-found.nx");
+    assert_panic("lang/spec/panic-variable-not-found.nx", "Undeclared `y`");
+}
+
+#[test]
+fn panic_self_outside_struct() {
+    assert_panic(
+        "lang/spec/panic-self-outside-struct.nx",
+        "`Self` is only available within a struct",
+    );
+}
+
+#[test]
+fn panic_impl_not_imported() {
+    assert_panic(
+        "lang/spec/panic-impl-not-imported.nx",
+        "Undeclared `self?` for `Bool`",
+    );
+}
+
+#[test]
+fn panic_impl_conflict() {
+    assert_panic(
+        "lang/spec/panic-impl-conflict.nx",
+        "`self?` already declared for `Bool`",
+    );
+}
+
+#[test]
+fn panic_namespace_private() {
+    assert_panic(
+        "lang/spec/panic-namespace-private.nx",
+        "`private` is private to namespace `Net`",
+    );
+}
+
+#[test]
+fn panic_closure_not_captured() {
+    assert_panic(
+        "lang/spec/panic-closure-not-captured.nx",
+        "`x` is not captured by the closure",
+    );
+}
+
+#[test]
+fn panic_capture_by_ref_escape() {
+    assert_panic(
+        "lang/spec/panic-capture-by-ref-escape.nx",
+        "Closure capturing local variables by reference may outlive them",
+    );
+}
+
+#[test]
+fn panic_extern_unsafe_type() {
+    assert_panic(
+        "lang/spec/panic-extern-unsafe-type.nx",
+        "`lang/spec/panic-extern-unsafe-type::Foo` is not FFI-safe",
+    );
+}
+
+#[test]
+fn panic_variadic_unsafe_type() {
+    assert_panic(
+        "lang/spec/panic-variadic-unsafe-type.nx",
+        "`builtin/string::String` is not FFI-safe",
+    );
+}
+
+#[test]
+fn panic_export_unsafe_type() {
+    assert_panic(
+        "lang/spec/panic-export-unsafe-type.nx",
+        "-> `builtin/bool::Bool` is not FFI-safe",
+    );
+}
+
+#[test]
+fn panic_cimport_not_found() {
+    assert_panic(
+        "lang/spec/panic-cimport-not-found.nx",
+        "Failed to read C header",
+    );
+}
+
+#[test]
+fn panic_format_argument_count() {
+    assert_panic(
+        "lang/spec/panic-format-argument-count.nx",
+        "Missing argument for the placeholder",
+    );
+}
+
+#[test]
+fn panic_interpolation_undeclared() {
+    assert_panic(
+        "lang/spec/panic-interpolation-undeclared.nx",
+        "Undeclared `missing`",
+    );
+}
+
+#[test]
+fn panic_interpolation_empty() {
+    assert_panic(
+        "lang/spec/panic-interpolation-empty.nx",
+        "Empty interpolation",
+    );
+}
+
+#[test]
+fn panic_unreachable_code() {
+    assert_panic("lang/spec/panic-unreachable-code.nx", "Unreachable code");
+}
+
+#[test]
+fn error() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/error.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn panic_unhandled_error() {
+    assert_panic(
+        "lang/spec/panic-unhandled-error.nx",
+        "Unhandled error(s) `Missing`",
+    );
+}
+
+#[test]
+fn panic_undeclared_error() {
+    assert_panic(
+        "lang/spec/panic-undeclared-error.nx",
+        "Cannot propagate undeclared error `Missing`",
+    );
+}
+
+#[test]
+fn optional() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/optional.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn declaration_order() {
+    let program = Program::new(".cache".into());
+    Program::run(
+        program,
+        "lang/spec/declaration-order.nx".into(),
+        "zig".into(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn import_alias() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/import-alias.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn import_paths() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/import-paths.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn init_order() {
+    let program = Program::new(".cache".into());
+    Program::resolve(program.clone(), "lang/spec/init-order.nx".into()).unwrap();
+
+    let paths: Vec<_> = program
+        .borrow()
+        .init_order()
+        .iter()
+        .map(|unit| unit.borrow().path.display().to_string())
+        .filter(|path| !path.starts_with("builtin"))
+        .collect();
+
+    assert_eq!(
+        paths,
+        [
+            "lang/spec/init/a.nx",
+            "lang/spec/init/b.nx",
+            "lang/spec/init-order.nx"
+        ]
+    );
+}
+
+#[test]
+fn init_order_run() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/init-order.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn globals() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/globals.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn panic_global_assign_imported() {
+    assert_panic(
+        "lang/spec/panic-global-assign-imported.nx",
+        "Cannot assign to imported variable `ready`",
+    );
+}
+
+#[test]
+fn panic_global_order() {
+    assert_panic("lang/spec/panic-global-order.nx", "Undeclared `b`");
+}
+
+#[test]
+fn panic_global_uninitialized_call() {
+    assert_panic(
+        "lang/spec/panic-global-uninitialized-call.nx",
+        "`ready` may be read before its initialization",
+    );
+}
+
+#[test]
+fn panic_pub_local() {
+    assert_panic(
+        "lang/spec/panic-pub-local.nx",
+        "Only module-level variables may be `pub`",
+    );
+}
+
+#[test]
+fn import_paths_canonical() {
+    let program = Program::new(".cache".into());
+    let unit = Program::resolve(program, "lang/spec/import-paths.nx".into()).unwrap();
+
+    // `import/private.nx` is imported thrice, and `import/index.nx` once.
+    assert_eq!(unit.borrow().dependencies.len(), 2);
+}
+
+#[test]
+fn panic_import_collision() {
+    assert_panic(
+        "lang/spec/panic-import-collision.nx",
+        "public already declared",
+    );
+}
+
+#[test]
+fn panic_import_cycle() {
+    assert_panic("lang/spec/panic-import-cycle.nx", "Cyclic import");
+}
+
+#[test]
+fn panic_import_not_found() {
+    assert_panic(
+        "lang/spec/panic-import-not-found.nx",
+        "Cannot find module \"./import/missing\"",
+    );
+}
+
+#[test]
+fn search_path() {
+    let program = Program::new(".cache".into());
+    program.borrow_mut().search_paths = vec!["lang/spec/packages".into()];
+    Program::run(program, "lang/spec/search-path.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn package() {
+    let manifest = Manifest::load("lang/spec/package/nx.toml".as_ref()).unwrap();
+    let program = Program::new(".cache".into());
+    program.borrow_mut().packages = package::resolve(&manifest).unwrap();
+    program.borrow_mut().optimize = manifest.build.optimize;
+    Program::run(program, manifest.entry_path(), "zig".into()).unwrap()
+}
+
+#[test]
+fn package_versions() {
+    let manifest = Manifest::load("lang/spec/package/nx.toml".as_ref()).unwrap();
+    let packages = package::resolve(&manifest).unwrap();
+
+    let names: Vec<_> = packages.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["package", "collections", "strings"]);
+
+    // The highest version matching `^1`.
+    assert_eq!(packages[2].version, Some(semver::Version::new(1, 2, 0)));
+
+    // Resolution never writes the lockfile, thus check the committed one instead.
+    assert_eq!(
+        package::lockfile(&packages),
+        Lockfile::load(&manifest.lock_path()).unwrap()
+    );
+}
+
+#[test]
+fn panic_package_version() {
+    let manifest = Manifest::load("lang/spec/panic-package-version/nx.toml".as_ref()).unwrap();
+    assert!(package::resolve(&manifest).is_err());
+}
+
+#[test]
+fn panic_search_path_not_found() {
+    // Without search paths, a bare specifier is only looked up next to the importing file.
+    assert_panic(
+        "lang/spec/search-path.nx",
+        "Cannot find module \"collections/list\"",
+    );
+}
+
+#[test]
+fn panic_import_private() {
+    assert_panic("lang/spec/panic-import-private.nx", "`secret` is private");
+}
+
+#[test]
+fn panic_struct_identity() {
+    assert_panic("lang/spec/panic-struct-identity.nx", "expected struct `lang/spec/identity/foo::Foo`, got struct `lang/spec/panic-struct-identity::Foo`");
+}
+
+#[test]
+fn panic_never_variable() {
+    assert_panic(
+        "lang/spec/panic-never-variable.nx",
+        "Expression never returns",
+    );
+}
+
+#[test]
+fn generics() {
+    let program = Program::new(".cache".into());
+    Program::run(program, "lang/spec/generics.nx".into(), "zig".into()).unwrap()
+}
+
+#[test]
+fn panic_generic_arity() {
+    assert_panic(
+        "lang/spec/panic-generic-arity.nx",
+        "expects 2 type arguments, got 1",
+    );
+}
+
+#[test]
+fn panic_optional_not_narrowed() {
+    assert_panic(
+        "lang/spec/panic-optional-not-narrowed.nx",
+        "returned optional `builtin/bool::Bool`?",
+    );
+}
+
+#[test]
+fn panic_narrow_captured_by_ref() {
+    assert_panic(
+        "lang/spec/panic-narrow-captured-by-ref.nx",
+        "returned optional `builtin/bool::Bool`?",
+    );
+}
+
+#[test]
+fn panic_capture_narrowed() {
+    assert_panic(
+        "lang/spec/panic-capture-narrowed.nx",
+        "Cannot capture `y` by reference while it is narrowed",
+    );
+}
+
+#[test]
+fn panic_narrow_global() {
+    assert_panic(
+        "lang/spec/panic-narrow-global.nx",
+        "returned optional `builtin/bool::Bool`?",
+    );
 }